@@ -8,6 +8,8 @@ use criterion::{
     Throughput,
 };
 
+use bit_vec::BitVec;
+use rayon::prelude::*;
 use roaring::{MultiOps, RoaringBitmap, RoaringTreemap};
 
 use crate::datasets::Datasets;
@@ -187,11 +189,54 @@ fn creation(c: &mut Criterion) {
                 }
             })
         });
+
+        group.bench_function(BenchmarkId::new("collect_shuffled", &dataset.name), |b| {
+            // A deterministic shuffle, multiplying by an odd constant is a bijection
+            let shuffled_numbers = dataset_numbers
+                .iter()
+                .map(|bitmap_numbers| {
+                    let mut numbers = bitmap_numbers.clone();
+                    numbers.sort_unstable_by_key(|n| n.wrapping_mul(0x9E37_79B9));
+                    numbers
+                })
+                .collect::<Vec<_>>();
+            b.iter(|| {
+                for bitmap_numbers in &shuffled_numbers {
+                    black_box(bitmap_numbers.iter().copied().collect::<RoaringBitmap>());
+                }
+            })
+        });
     }
 
     group.finish();
 }
 
+fn bit_vec_conversion(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bit_vec_conversion");
+
+    // A large and mostly dense BitVec, one bit in ten is unset
+    let bits = BitVec::from_fn(1 << 26, |i| i % 10 != 0);
+    let bitmap = RoaringBitmap::from(&bits);
+    group.throughput(Throughput::Elements(bits.len() as u64));
+
+    group.bench_function("from_bit_vec", |b| {
+        b.iter(|| black_box(RoaringBitmap::from(&bits)));
+    });
+
+    group.bench_function("collect_bit_vec", |b| {
+        b.iter(|| {
+            let values = bits.iter().enumerate().filter(|(_, bit)| *bit).map(|(i, _)| i as u32);
+            black_box(RoaringBitmap::from_sorted_iter(values).unwrap())
+        });
+    });
+
+    group.bench_function("to_bit_vec", |b| {
+        b.iter(|| black_box(BitVec::from(&bitmap)));
+    });
+
+    group.finish();
+}
+
 fn len(c: &mut Criterion) {
     let mut group = c.benchmark_group("len");
 
@@ -266,7 +311,22 @@ fn and(c: &mut Criterion) {
         |a, b| BitAndAssign::bitand_assign(a, b),
         |a, b| BitAndAssign::bitand_assign(a, b),
         |a, b| a.intersection_len(b),
-    )
+    );
+
+    let mut group = c.benchmark_group("pairwise_and");
+    let a: RoaringBitmap = (0..4_000_000).step_by(3).collect();
+    let b: RoaringBitmap = (0..4_000_000).step_by(2).collect();
+    let len = a.intersection_len(&b);
+    group.bench_function("len_at_least_reached_early", |bench| {
+        bench.iter(|| black_box(&a).intersection_len_at_least(black_box(&b), 1000));
+    });
+    group.bench_function("len_at_least_never_reached", |bench| {
+        bench.iter(|| black_box(&a).intersection_len_at_least(black_box(&b), len + 1));
+    });
+    group.bench_function("len", |bench| {
+        bench.iter(|| black_box(&a).intersection_len(black_box(&b)) >= 1000);
+    });
+    group.finish();
 }
 
 #[allow(clippy::redundant_closure)]
@@ -281,7 +341,15 @@ fn or(c: &mut Criterion) {
         |a, b| BitOrAssign::bitor_assign(a, b),
         |a, b| BitOrAssign::bitor_assign(a, b),
         |a, b| a.union_len(b),
-    )
+    );
+
+    let mut group = c.benchmark_group("pairwise_or");
+    let a: RoaringBitmap = (1..100).collect();
+    let b: RoaringBitmap = (100..200).collect();
+    group.bench_function("disjoint_arrays", |bench| {
+        bench.iter(|| black_box(&a) | black_box(&b));
+    });
+    group.finish();
 }
 
 #[allow(clippy::redundant_closure)]
@@ -296,7 +364,24 @@ fn sub(c: &mut Criterion) {
         |a, b| SubAssign::sub_assign(a, b),
         |a, b| SubAssign::sub_assign(a, b),
         |a, b| a.difference_len(b),
-    )
+    );
+
+    let mut group = c.benchmark_group("pairwise_sub");
+    let a: RoaringBitmap = (0..2_000_000).step_by(3).collect();
+    let b: RoaringBitmap = (2_000_000..4_000_000).step_by(3).collect();
+    group.bench_function("len_disjoint", |bench| {
+        bench.iter(|| black_box(&a).difference_len(black_box(&b)));
+    });
+
+    let a: RoaringBitmap = (0..4_000_000).filter(|x| x % 3 != 0).collect();
+    let b: RoaringBitmap = (0..4_000_000).step_by(2).collect();
+    group.bench_function("large_ref_ref", |bench| {
+        bench.iter(|| black_box(&a) - black_box(&b));
+    });
+    group.bench_function("large_owned_ref", |bench| {
+        bench.iter_batched(|| a.clone(), |a| a - black_box(&b), BatchSize::LargeInput);
+    });
+    group.finish();
 }
 
 #[allow(clippy::redundant_closure)]
@@ -352,6 +437,22 @@ fn iteration(c: &mut Criterion) {
             );
         });
 
+        group.bench_function(BenchmarkId::new("fill_buffer", &dataset.name), |b| {
+            let mut buf = Vec::with_capacity(4096);
+            b.iter(|| {
+                for bitmap in &dataset.bitmaps {
+                    let mut iter = bitmap.iter();
+                    loop {
+                        buf.clear();
+                        if iter.fill_buffer(&mut buf, 4096) == 0 {
+                            break;
+                        }
+                        black_box(&buf);
+                    }
+                }
+            });
+        });
+
         group.bench_function(BenchmarkId::new("iter rev", &dataset.name), |b| {
             b.iter(|| {
                 for i in dataset.bitmaps.iter().flat_map(|bitmap| bitmap.iter().rev()) {
@@ -373,6 +474,105 @@ fn iteration(c: &mut Criterion) {
         });
     }
 
+    // Long runs of consecutive values, stored in bitset containers
+    let runs: RoaringBitmap = (0..64u32).flat_map(|i| i * 100_000..i * 100_000 + 50_000).collect();
+    group.throughput(Throughput::Elements(runs.len()));
+
+    group.bench_function("iter runs", |b| {
+        b.iter(|| {
+            for i in runs.iter() {
+                black_box(i);
+            }
+        });
+    });
+
+    group.bench_function("fill_buffer runs", |b| {
+        let mut buf = Vec::with_capacity(4096);
+        b.iter(|| {
+            let mut iter = runs.iter();
+            loop {
+                buf.clear();
+                if iter.fill_buffer(&mut buf, 4096) == 0 {
+                    break;
+                }
+                black_box(&buf);
+            }
+        });
+    });
+
+    group.finish();
+}
+
+fn iteration_advance_to(c: &mut Criterion) {
+    let mut group = c.benchmark_group("iteration_advance_to");
+    // Every third value of the first 256 keys, only bitmap containers
+    let bitmap: RoaringBitmap = (0..1 << 24).step_by(3).collect();
+
+    for stride in [64u32, 1024, 16384] {
+        group.bench_with_input(BenchmarkId::new("advance_to", stride), &stride, |b, &stride| {
+            b.iter(|| {
+                let mut iter = bitmap.iter();
+                let mut target = 0;
+                loop {
+                    iter.advance_to(target);
+                    match iter.next() {
+                        Some(value) => target = black_box(value).saturating_add(stride),
+                        None => break,
+                    }
+                }
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("next", stride), &stride, |b, &stride| {
+            b.iter(|| {
+                let mut iter = bitmap.iter();
+                let mut target = 0;
+                while let Some(value) = iter.find(|&value| value >= target) {
+                    target = black_box(value).saturating_add(stride);
+                }
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn parallel_iteration(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parallel_iteration");
+    let bitmap = {
+        let mut rb = RoaringBitmap::new();
+        rb.insert_range(0..100_000_000);
+        rb
+    };
+    group.throughput(Throughput::Elements(bitmap.len()));
+
+    group.bench_function("iter sum", |b| {
+        b.iter(|| black_box(&bitmap).iter().map(u64::from).sum::<u64>());
+    });
+
+    group.bench_function("par_iter sum", |b| {
+        b.iter(|| black_box(&bitmap).par_iter().map(u64::from).sum::<u64>());
+    });
+
+    group.finish();
+}
+
+fn parallel_collect(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parallel_collect");
+    let values: Vec<u32> = (0..10_000_000u32).map(|i| i.wrapping_mul(2_654_435_761)).collect();
+    group.throughput(Throughput::Elements(values.len() as u64));
+
+    group.bench_function("par_iter collect vec then bitmap", |b| {
+        b.iter(|| {
+            let values: Vec<u32> = black_box(&values).par_iter().copied().collect();
+            values.into_iter().collect::<RoaringBitmap>()
+        });
+    });
+
+    group.bench_function("par_iter collect bitmap", |b| {
+        b.iter(|| black_box(&values).par_iter().copied().collect::<RoaringBitmap>());
+    });
+
     group.finish();
 }
 
@@ -441,6 +641,26 @@ fn deserialization(c: &mut Criterion) {
                 }
             });
         });
+
+        group.bench_function(BenchmarkId::new("deserialize_from & union", &dataset.name), |b| {
+            b.iter(|| {
+                let mut union = RoaringBitmap::new();
+                for buf in input.iter() {
+                    union |= RoaringBitmap::deserialize_from(buf.as_slice()).unwrap();
+                }
+                black_box(union)
+            });
+        });
+
+        group.bench_function(BenchmarkId::new("union_with_serialized", &dataset.name), |b| {
+            b.iter(|| {
+                let mut union = RoaringBitmap::new();
+                for buf in input.iter() {
+                    union.union_with_serialized(buf).unwrap();
+                }
+                black_box(union)
+            });
+        });
     }
 
     group.finish();
@@ -532,6 +752,23 @@ fn successive_or(c: &mut Criterion) {
             );
         });
 
+        group.bench_function(
+            BenchmarkId::new("Successive Or Assign Ref Redundant", &dataset.name),
+            |b| {
+                let union = dataset.bitmaps.iter().union();
+                b.iter_batched(
+                    || union.clone(),
+                    |mut output| {
+                        for bitmap in &dataset.bitmaps {
+                            output |= bitmap;
+                        }
+                        output
+                    },
+                    BatchSize::LargeInput,
+                );
+            },
+        );
+
         group.bench_function(BenchmarkId::new("Successive Or Ref Ref", &dataset.name), |b| {
             b.iter(|| {
                 let mut output = RoaringBitmap::new();
@@ -541,6 +778,18 @@ fn successive_or(c: &mut Criterion) {
             });
         });
 
+        group.bench_function(BenchmarkId::new("Union All Owned", &dataset.name), |b| {
+            b.iter_batched(
+                || dataset.bitmaps.clone(),
+                |bitmaps: Vec<RoaringBitmap>| {
+                    let mut output = RoaringBitmap::new();
+                    output.union_all(bitmaps);
+                    output
+                },
+                BatchSize::LargeInput,
+            );
+        });
+
         group.bench_function(BenchmarkId::new("Multi Or Ref", &dataset.name), |b| {
             b.iter(|| black_box(dataset.bitmaps.iter().union()));
         });
@@ -604,6 +853,22 @@ fn insert(c: &mut Criterion) {
         });
     });
 
+    c.bench_function("fill & clear", |b| {
+        let mut bitmap = RoaringBitmap::new();
+        b.iter(|| {
+            bitmap.extend((0..100).map(|i| black_box(i << 16)));
+            bitmap.clear();
+        });
+    });
+
+    c.bench_function("fill & clear_and_shrink", |b| {
+        let mut bitmap = RoaringBitmap::new();
+        b.iter(|| {
+            bitmap.extend((0..100).map(|i| black_box(i << 16)));
+            bitmap.clear_and_shrink();
+        });
+    });
+
     c.bench_function("insert several", |b| {
         let mut bitmap = RoaringBitmap::new();
         b.iter(|| {
@@ -635,6 +900,31 @@ fn contains(c: &mut Criterion) {
             bitmap.contains(black_box(1));
         });
     });
+
+    c.bench_function("contains_range array scan", |b| {
+        // Runs of 8 values every 16 values, in a single array container
+        let bitmap: RoaringBitmap = (0..4000).filter(|i| i % 16 < 8).collect();
+
+        b.iter(|| {
+            for start in (0..4000).step_by(4) {
+                black_box(bitmap.contains_range(black_box(start)..=black_box(start + 3)));
+            }
+        });
+    });
+
+    // Clustered queries in the dense bitset containers of the first keys
+    let bitmap: RoaringBitmap = (0..1 << 20).filter(|i| i % 3 != 0).collect();
+    let values: Vec<u32> = (0..1 << 20).step_by(5).collect();
+
+    c.bench_function("membership_bools bitset", |b| {
+        b.iter(|| black_box(bitmap.membership_bools(black_box(&values))));
+    });
+
+    c.bench_function("contains loop bitset", |b| {
+        b.iter(|| {
+            black_box(values.iter().map(|&value| bitmap.contains(value)).collect::<Vec<bool>>())
+        });
+    });
 }
 
 fn remove(c: &mut Criterion) {
@@ -693,6 +983,83 @@ fn insert_range_bitmap(c: &mut Criterion) {
     }
 }
 
+fn retain_ranges(c: &mut Criterion) {
+    let mut group = c.benchmark_group("retain_ranges");
+    let ranges = [0..=999_999, 2_000_000..=2_999_999, 10_000_000..=u32::MAX / 2];
+
+    for dataset in Datasets {
+        group.bench_function(BenchmarkId::new("retain_ranges", &dataset.name), |b| {
+            b.iter_batched(
+                || dataset.bitmaps.clone(),
+                |mut bitmaps| {
+                    for bitmap in &mut bitmaps {
+                        bitmap.retain_ranges(&ranges);
+                    }
+                    bitmaps
+                },
+                BatchSize::LargeInput,
+            );
+        });
+
+        group.bench_function(BenchmarkId::new("and ranges bitmap", &dataset.name), |b| {
+            b.iter_batched(
+                || dataset.bitmaps.clone(),
+                |mut bitmaps| {
+                    for bitmap in &mut bitmaps {
+                        let mut keep = RoaringBitmap::new();
+                        for range in &ranges {
+                            keep.insert_range(range.clone());
+                        }
+                        *bitmap &= keep;
+                    }
+                    bitmaps
+                },
+                BatchSize::LargeInput,
+            );
+        });
+    }
+
+    group.finish();
+}
+
+fn intersect_with_sorted_slice(c: &mut Criterion) {
+    let mut group = c.benchmark_group("intersect_with_sorted_slice");
+    // Every seventh value of the first 64 keys, dense and sparse containers
+    let sorted: Vec<u32> = (0..1 << 22).step_by(7).collect();
+
+    for dataset in Datasets {
+        group.bench_function(BenchmarkId::new("intersect_with_sorted_slice", &dataset.name), |b| {
+            b.iter_batched(
+                || dataset.bitmaps.clone(),
+                |mut bitmaps| {
+                    for bitmap in &mut bitmaps {
+                        bitmap.intersect_with_sorted_slice(&sorted);
+                    }
+                    bitmaps
+                },
+                BatchSize::LargeInput,
+            );
+        });
+
+        group.bench_function(BenchmarkId::new("and from_sorted_iter", &dataset.name), |b| {
+            b.iter_batched(
+                || dataset.bitmaps.clone(),
+                |mut bitmaps| {
+                    for bitmap in &mut bitmaps {
+                        let filter =
+                            RoaringBitmap::from_sorted_iter(sorted.iter().copied()).unwrap();
+                        *bitmap &= filter;
+                    }
+                    bitmaps
+                },
+                BatchSize::LargeInput,
+            );
+        });
+    }
+
+    group.finish();
+}
+
 fn insert_range_treemap(c: &mut Criterion) {
     for &size in &[1_000_u64, 10_000u64, 2 * (u32::MAX as u64)] {
         let mut group = c.benchmark_group("insert_range_treemap");
@@ -720,6 +1087,7 @@ fn insert_range_treemap(c: &mut Criterion) {
 criterion_group!(
     benches,
     creation,
+    bit_vec_conversion,
     insert,
     contains,
     len,
@@ -735,7 +1103,12 @@ criterion_group!(
     remove_range_bitmap,
     insert_range_bitmap,
     insert_range_treemap,
+    retain_ranges,
+    intersect_with_sorted_slice,
     iteration,
+    iteration_advance_to,
+    parallel_iteration,
+    parallel_collect,
     is_empty,
     serialization,
     deserialization,
@@ -0,0 +1,108 @@
+use ::bit_vec::BitVec;
+
+use super::container::Container;
+use super::store::BITMAP_LENGTH;
+use crate::RoaringBitmap;
+
+impl From<&BitVec> for RoaringBitmap {
+    /// Creates a `RoaringBitmap` containing the positions of the bits set in the `BitVec`.
+    ///
+    /// The blocks of the `BitVec` are read directly, each group of 65536 bits becomes an
+    /// array or a bitset container depending on the number of bits set.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the `BitVec` is longer than 2^32 bits.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bit_vec::BitVec;
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let bits = BitVec::from_fn(100_000, |i| i % 3 == 0);
+    /// let rb = RoaringBitmap::from(&bits);
+    /// assert_eq!(rb.len(), 33_334);
+    /// assert!(rb.contains(99_999));
+    /// ```
+    fn from(bits: &BitVec) -> RoaringBitmap {
+        assert!(bits.len() as u64 <= 1 << 32, "the BitVec must be at most 2^32 bits long");
+
+        // Each container spans 1024 words of 64 bits, that is 2048 blocks of 32 bits
+        let mut words = [0u64; BITMAP_LENGTH];
+        let containers = bits
+            .storage()
+            .chunks(2 * BITMAP_LENGTH)
+            .enumerate()
+            .filter_map(|(key, blocks)| {
+                for (word, pair) in words.iter_mut().zip(blocks.chunks(2)) {
+                    let high = pair.get(1).map_or(0, |&block| u64::from(block) << 32);
+                    *word = u64::from(pair[0]) | high;
+                }
+                Container::from_words(key as u16, &words[..blocks.len().div_ceil(2)])
+            })
+            .collect();
+
+        RoaringBitmap { containers }
+    }
+}
+
+impl From<&RoaringBitmap> for BitVec {
+    /// Creates a `BitVec` with the bits set at the values of the `RoaringBitmap`.
+    ///
+    /// The `BitVec` is as long as the maximum value plus one, it is empty when the
+    /// bitmap is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bit_vec::BitVec;
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb = RoaringBitmap::from([1, 3]);
+    /// let bits = BitVec::from(&rb);
+    /// assert!(bits.eq_vec(&[false, true, false, true]));
+    /// ```
+    fn from(bitmap: &RoaringBitmap) -> BitVec {
+        let len = bitmap.max().map_or(0, |max| max as usize + 1);
+        let mut bits = BitVec::from_elem(len, false);
+        for value in bitmap {
+            bits.set(value as usize, true);
+        }
+        bits
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ::bit_vec::BitVec;
+    use proptest::prelude::*;
+
+    use crate::RoaringBitmap;
+
+    proptest! {
+        #[test]
+        fn test_bit_vec(
+            bitmap in RoaringBitmap::arbitrary(),
+        ) {
+            let bits = BitVec::from(&bitmap);
+            prop_assert_eq!(bits.len() as u64, bitmap.max().map_or(0, |max| u64::from(max) + 1));
+            prop_assert_eq!(bits.count_ones(), bitmap.len());
+
+            let roundtrip = RoaringBitmap::from(&bits);
+            prop_assert_eq!(roundtrip.validate(), Ok(()));
+            prop_assert_eq!(roundtrip, bitmap);
+        }
+    }
+
+    #[test]
+    fn test_bit_vec_partial_block() {
+        // 4096 bits set is still an array container, one more makes it a bitset
+        for len in [4096, 4097, 65_536 + 33] {
+            let bits = BitVec::from_elem(len, true);
+            let rb = RoaringBitmap::from(&bits);
+            assert_eq!(rb.validate(), Ok(()));
+            assert_eq!(rb, (0..len as u32).collect::<RoaringBitmap>());
+        }
+    }
+}
@@ -11,18 +11,37 @@ pub const ARRAY_LIMIT: u64 = 4096;
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 
-#[derive(PartialEq, Clone)]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "rkyv", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
 pub struct Container {
     pub key: u16,
     pub store: Store,
 }
 
+impl Clone for Container {
+    fn clone(&self) -> Self {
+        Container { key: self.key, store: self.store.clone() }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.key = source.key;
+        self.store.clone_from(&source.store);
+    }
+}
+
 #[derive(Clone)]
 pub struct Iter<'a> {
     pub key: u16,
     inner: store::Iter<'a>,
 }
 
+/// An iterator over the maximal runs of a container, in ascending order.
+#[derive(Clone)]
+pub struct Runs<'a> {
+    key: u16,
+    inner: store::Runs<'a>,
+}
+
 impl Container {
     pub fn new(key: u16) -> Container {
         Container { key, store: Store::new() }
@@ -35,6 +54,10 @@ impl Container {
     pub fn from_lsb0_bytes(key: u16, bytes: &[u8], byte_offset: usize) -> Option<Self> {
         Some(Container { key, store: Store::from_lsb0_bytes(bytes, byte_offset)? })
     }
+
+    pub fn from_words(key: u16, words: &[u64]) -> Option<Self> {
+        Some(Container { key, store: Store::from_words(words)? })
+    }
 }
 
 impl Container {
@@ -56,6 +79,15 @@ impl Container {
         }
     }
 
+    /// Inserts the index and returns whether it was new along with its rank in the container.
+    pub fn insert_and_rank(&mut self, index: u16) -> (bool, u64) {
+        let (inserted, rank) = self.store.insert_and_rank(index);
+        if inserted {
+            self.ensure_correct_store();
+        }
+        (inserted, rank)
+    }
+
     pub fn insert_range(&mut self, range: RangeInclusive<u16>) -> u64 {
         // If inserting the range will make this a bitmap by itself, do it now
         if range.len() as u64 > ARRAY_LIMIT {
@@ -107,12 +139,25 @@ impl Container {
         result
     }
 
+    pub fn flip_range(&mut self, range: RangeInclusive<u16>) -> i64 {
+        // If flipping the range may make this a bitmap by itself, do it now
+        if range.len() as u64 > ARRAY_LIMIT {
+            if let Store::Array(arr) = &self.store {
+                self.store = Store::Bitmap(arr.to_bitmap_store());
+            }
+        }
+        let change = self.store.flip_range(range);
+        self.ensure_correct_store();
+        change
+    }
+
     pub fn remove_smallest(&mut self, n: u64) {
         match &self.store {
             Store::Bitmap(bits) => {
-                if bits.len() - n <= ARRAY_LIMIT {
-                    let mut replace_array = Vec::with_capacity((bits.len() - n) as usize);
-                    replace_array.extend(bits.iter().skip(n as usize));
+                let len = bits.len().saturating_sub(n);
+                if len <= ARRAY_LIMIT {
+                    let mut replace_array = Vec::with_capacity(len as usize);
+                    replace_array.extend(bits.iter().skip((bits.len() - len) as usize));
                     self.store = Store::Array(store::ArrayStore::from_vec_unchecked(replace_array));
                 } else {
                     self.store.remove_smallest(n)
@@ -125,9 +170,10 @@ impl Container {
     pub fn remove_biggest(&mut self, n: u64) {
         match &self.store {
             Store::Bitmap(bits) => {
-                if bits.len() - n <= ARRAY_LIMIT {
-                    let mut replace_array = Vec::with_capacity((bits.len() - n) as usize);
-                    replace_array.extend(bits.iter().take((bits.len() - n) as usize));
+                let len = bits.len().saturating_sub(n);
+                if len <= ARRAY_LIMIT {
+                    let mut replace_array = Vec::with_capacity(len as usize);
+                    replace_array.extend(bits.iter().take(len as usize));
                     self.store = Store::Array(store::ArrayStore::from_vec_unchecked(replace_array));
                 } else {
                     self.store.remove_biggest(n)
@@ -170,10 +216,20 @@ impl Container {
         self.store.max()
     }
 
+    pub fn runs(&self) -> Runs<'_> {
+        Runs { key: self.key, inner: self.store.runs() }
+    }
+
     pub fn rank(&self, index: u16) -> u64 {
         self.store.rank(index)
     }
 
+    /// Returns `true` if merging this container into `other` would not change it,
+    /// only from their cardinalities so that the check never visits the values.
+    fn is_redundant_in(&self, other: &Self) -> bool {
+        self.is_empty() || other.is_full()
+    }
+
     pub(crate) fn ensure_correct_store(&mut self) {
         match &self.store {
             Store::Bitmap(ref bits) => {
@@ -203,6 +259,9 @@ impl BitOr<&Container> for &Container {
 
 impl BitOrAssign<Container> for Container {
     fn bitor_assign(&mut self, rhs: Container) {
+        if rhs.is_redundant_in(self) {
+            return;
+        }
         BitOrAssign::bitor_assign(&mut self.store, rhs.store);
         self.ensure_correct_store();
     }
@@ -210,6 +269,9 @@ impl BitOrAssign<Container> for Container {
 
 impl BitOrAssign<&Container> for Container {
     fn bitor_assign(&mut self, rhs: &Container) {
+        if rhs.is_redundant_in(self) {
+            return;
+        }
         BitOrAssign::bitor_assign(&mut self.store, &rhs.store);
         self.ensure_correct_store();
     }
@@ -302,6 +364,15 @@ impl IntoIterator for Container {
     }
 }
 
+impl Iterator for Runs<'_> {
+    type Item = RangeInclusive<u32>;
+
+    fn next(&mut self) -> Option<RangeInclusive<u32>> {
+        let run = self.inner.next()?;
+        Some(util::join(self.key, *run.start())..=util::join(self.key, *run.end()))
+    }
+}
+
 impl Iterator for Iter<'_> {
     type Item = u32;
     fn next(&mut self) -> Option<u32> {
@@ -328,11 +399,23 @@ impl DoubleEndedIterator for Iter<'_> {
     fn next_back(&mut self) -> Option<Self::Item> {
         self.inner.next_back().map(|i| util::join(self.key, i))
     }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.inner.nth_back(n).map(|i| util::join(self.key, i))
+    }
 }
 
 impl ExactSizeIterator for Iter<'_> {}
 
 impl Iter<'_> {
+    pub(crate) fn peek(&self) -> Option<u32> {
+        self.inner.peek().map(|i| util::join(self.key, i))
+    }
+
+    pub(crate) fn peek_back(&self) -> Option<u32> {
+        self.inner.peek_back().map(|i| util::join(self.key, i))
+    }
+
     pub(crate) fn advance_to(&mut self, index: u16) {
         self.inner.advance_to(index);
     }
@@ -340,6 +423,10 @@ impl Iter<'_> {
     pub(crate) fn advance_back_to(&mut self, index: u16) {
         self.inner.advance_back_to(index);
     }
+
+    pub(crate) fn fill_buffer(&mut self, buf: &mut Vec<u32>, max: usize) -> usize {
+        self.inner.fill_buffer(self.key, buf, max)
+    }
 }
 
 impl fmt::Debug for Container {
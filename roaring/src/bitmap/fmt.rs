@@ -5,9 +5,20 @@ use crate::RoaringBitmap;
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 
+/// The maximum number of ranges listed by the alternate (`{:#?}`) representation.
+const MAX_DEBUG_RANGES: usize = 16;
+
 impl fmt::Debug for RoaringBitmap {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self.len() < 16 {
+        if f.alternate() {
+            let statistics = self.statistics();
+            f.debug_struct("RoaringBitmap")
+                .field("len", &statistics.cardinality)
+                .field("n_array_containers", &statistics.n_array_containers)
+                .field("n_bitset_containers", &statistics.n_bitset_containers)
+                .field("ranges", &DebugRanges(self))
+                .finish()
+        } else if self.len() < 16 {
             write!(f, "RoaringBitmap<{:?}>", self.iter().collect::<Vec<u32>>())
         } else {
             write!(
@@ -20,3 +31,86 @@ impl fmt::Debug for RoaringBitmap {
         }
     }
 }
+
+/// Formats the contiguous ranges of a bitmap as a set, e.g. `{0..=999, 2000, 5000..=5999}`.
+///
+/// Only the first [`MAX_DEBUG_RANGES`] ranges are listed, the rest is elided.
+struct DebugRanges<'a>(&'a RoaringBitmap);
+
+impl fmt::Debug for DebugRanges<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut set = f.debug_set();
+        let mut iter = self.0.iter();
+        let mut next = iter.next();
+        let mut listed = 0;
+
+        while let Some(start) = next.take() {
+            if listed == MAX_DEBUG_RANGES {
+                set.entry(&format_args!(".."));
+                break;
+            }
+
+            let mut end = start;
+            for value in iter.by_ref() {
+                if value == end + 1 {
+                    end = value;
+                } else {
+                    next = Some(value);
+                    break;
+                }
+            }
+
+            if start == end {
+                set.entry(&start);
+            } else {
+                set.entry(&(start..=end));
+            }
+            listed += 1;
+        }
+
+        set.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::RoaringBitmap;
+
+    #[cfg(not(feature = "std"))]
+    use alloc::format;
+
+    #[test]
+    fn debug_small() {
+        let bitmap: RoaringBitmap = (1..4).collect();
+        assert_eq!(format!("{:?}", bitmap), "RoaringBitmap<[1, 2, 3]>");
+    }
+
+    #[test]
+    fn debug_alternate_ranges() {
+        let mut bitmap: RoaringBitmap = (0..1000).collect();
+        bitmap.insert(2000);
+        bitmap.insert_range(5000..6000);
+        bitmap.insert_range(100_000..110_000);
+
+        let expected = "RoaringBitmap {
+    len: 12001,
+    n_array_containers: 1,
+    n_bitset_containers: 1,
+    ranges: {
+        0..=999,
+        2000,
+        5000..=5999,
+        100000..=109999,
+    },
+}";
+        assert_eq!(format!("{:#?}", bitmap), expected);
+    }
+
+    #[test]
+    fn debug_alternate_elides_ranges() {
+        let bitmap: RoaringBitmap = (0..100).map(|i| i * 2).collect();
+        let debug = format!("{:#?}", bitmap);
+        assert!(debug.contains("30,\n        ..,\n    },"));
+        assert!(!debug.contains("32,"));
+    }
+}
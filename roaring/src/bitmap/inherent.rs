@@ -1,10 +1,11 @@
 use core::cmp::Ordering;
-use core::ops::RangeBounds;
+use core::ops::{RangeBounds, RangeInclusive};
 
 use crate::RoaringBitmap;
 
 use super::container::Container;
 use super::util;
+use super::InsertOutcome;
 
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
@@ -61,6 +62,80 @@ impl RoaringBitmap {
         container.insert(index)
     }
 
+    /// Ensures a value is in the set and returns whether it was already present.
+    ///
+    /// This replaces a [`RoaringBitmap::contains`] followed by an [`RoaringBitmap::insert`] with
+    /// a single lookup. The value is always in the set afterward.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb = RoaringBitmap::new();
+    /// assert_eq!(rb.get_or_insert(3), false);
+    /// assert_eq!(rb.get_or_insert(3), true);
+    /// assert_eq!(rb.contains(3), true);
+    /// ```
+    #[inline]
+    pub fn get_or_insert(&mut self, value: u32) -> bool {
+        !self.insert(value)
+    }
+
+    /// Adds a value to the set, like [`RoaringBitmap::insert`], and reports whether the
+    /// container holding it had to change its kind to accommodate the new value.
+    ///
+    /// This is meant to diagnose insertion-heavy workloads, prefer `insert` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::bitmap::ContainerKind;
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb: RoaringBitmap = (0..4096).collect();
+    ///
+    /// let outcome = rb.insert_with_outcome(10);
+    /// assert!(!outcome.was_new);
+    /// assert_eq!(outcome.container_converted, None);
+    ///
+    /// let outcome = rb.insert_with_outcome(5000);
+    /// assert!(outcome.was_new);
+    /// assert_eq!(outcome.container_converted, Some((ContainerKind::Array, ContainerKind::Bitset)));
+    /// ```
+    pub fn insert_with_outcome(&mut self, value: u32) -> InsertOutcome {
+        let (key, index) = util::split(value);
+        let loc = self.find_container_by_key(key);
+        let container = &mut self.containers[loc];
+        let before = container.store.kind();
+        let was_new = container.insert(index);
+        let after = container.store.kind();
+        InsertOutcome { was_new, container_converted: (before != after).then_some((before, after)) }
+    }
+
+    /// Adds a value to the set and returns whether it was newly inserted along with its rank
+    /// in the set after the insertion, i.e. the number of integers that are <= value.
+    ///
+    /// This is cheaper than calling [`RoaringBitmap::insert`] then [`RoaringBitmap::rank`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb = RoaringBitmap::from_iter([1, 10, 100_000]);
+    ///
+    /// assert_eq!(rb.insert_and_rank(5), (true, 2));
+    /// assert_eq!(rb.insert_and_rank(10), (false, 3));
+    /// assert_eq!(rb.insert_and_rank(200_000), (true, 5));
+    /// ```
+    pub fn insert_and_rank(&mut self, value: u32) -> (bool, u64) {
+        let (key, index) = util::split(value);
+        let loc = self.find_container_by_key(key);
+        let (inserted, rank) = self.containers[loc].insert_and_rank(index);
+        (inserted, rank + self.containers[..loc].iter().map(|c| c.len()).sum::<u64>())
+    }
+
     /// Searches for the specific container by the given key.
     /// Creates a new container if it doesn't exist.
     ///
@@ -138,6 +213,182 @@ impl RoaringBitmap {
         inserted
     }
 
+    /// Inserts a range of values, like [`RoaringBitmap::insert_range`], and
+    /// also reports whether a container changed its kind.
+    ///
+    /// Returns the number of inserted values and `true` if one of the
+    /// containers already present was converted, e.g. from an array to a bitset.
+    /// The containers created for the range are not considered as converted.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb = RoaringBitmap::new();
+    /// assert_eq!(rb.insert_range_tracked(0..10), (10, false));
+    /// assert_eq!(rb.insert_range_tracked(10..5000), (4990, true));
+    /// assert_eq!(rb.insert_range_tracked(5000..6000), (1000, false));
+    /// ```
+    pub fn insert_range_tracked<R>(&mut self, range: R) -> (u64, bool)
+    where
+        R: RangeBounds<u32>,
+    {
+        let (start, end) = match util::convert_range_to_inclusive(range) {
+            Ok(range) => (*range.start(), *range.end()),
+            Err(_) => return (0, false),
+        };
+
+        let (start_container_key, start_index) = util::split(start);
+        let (end_container_key, end_index) = util::split(end);
+
+        let mut inserted = 0;
+        let mut converted = false;
+        for key in start_container_key..=end_container_key {
+            let (index, kind) = match self.containers.binary_search_by_key(&key, |c| c.key) {
+                Ok(loc) => (loc, Some(self.containers[loc].store.kind())),
+                Err(loc) => {
+                    self.containers.insert(loc, Container::new(key));
+                    (loc, None)
+                }
+            };
+
+            let low = if key == start_container_key { start_index } else { 0 };
+            let high = if key == end_container_key { end_index } else { u16::MAX };
+            let container = &mut self.containers[index];
+            inserted += container.insert_range(low..=high);
+            converted |= kind.is_some_and(|kind| kind != container.store.kind());
+        }
+
+        (inserted, converted)
+    }
+
+    /// Inserts many ranges of values, in any order.
+    /// Returns the number of inserted values.
+    ///
+    /// The ranges are sorted and the overlapping or adjacent ones are coalesced before being
+    /// inserted, a value covered by several ranges is only counted once. Each container is then
+    /// filled once per coalesced range, which is much cheaper than calling
+    /// [`RoaringBitmap::insert_range`] for every range when they cluster. Empty ranges are
+    /// ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb = RoaringBitmap::from([0]);
+    /// assert_eq!(rb.extend_ranges([20..=29, 0..=9, 5..=14, 15..=15]), 25);
+    /// assert_eq!(rb.iter().collect::<Vec<u32>>(), (0..=15).chain(20..=29).collect::<Vec<u32>>());
+    /// ```
+    pub fn extend_ranges<I>(&mut self, ranges: I) -> u64
+    where
+        I: IntoIterator<Item = RangeInclusive<u32>>,
+    {
+        let mut ranges: Vec<(u32, u32)> = ranges
+            .into_iter()
+            .filter(|range| !range.is_empty())
+            .map(|range| (*range.start(), *range.end()))
+            .collect();
+        ranges.sort_unstable();
+
+        let mut inserted = 0;
+        let mut ranges = ranges.into_iter();
+        let Some((mut start, mut end)) = ranges.next() else {
+            return 0;
+        };
+        for (next_start, next_end) in ranges {
+            if end.checked_add(1).map_or(true, |after_end| next_start <= after_end) {
+                end = end.max(next_end);
+            } else {
+                inserted += self.insert_range(start..=end);
+                (start, end) = (next_start, next_end);
+            }
+        }
+        inserted + self.insert_range(start..=end)
+    }
+
+    /// Inserts every value of `other` shifted by `offset`.
+    ///
+    /// When `offset` is a multiple of 2^16 the containers of `other` are rebased
+    /// on new keys, which is much faster than inserting the values one by one.
+    ///
+    /// The shifted values that would exceed `u32::MAX` are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let shard: RoaringBitmap = (0..3).collect();
+    ///
+    /// let mut rb = RoaringBitmap::new();
+    /// rb.insert_shifted(&shard, 1 << 16);
+    /// rb.insert_shifted(&shard, 10);
+    /// rb.insert_shifted(&shard, u32::MAX - 1);
+    ///
+    /// assert_eq!(rb.iter().collect::<Vec<u32>>(), [10, 11, 12, 65536, 65537, 65538, u32::MAX - 1, u32::MAX]);
+    /// ```
+    pub fn insert_shifted(&mut self, other: &RoaringBitmap, offset: u32) {
+        let (key_offset, index_offset) = util::split(offset);
+
+        if index_offset != 0 {
+            let shifted = other.iter().map_while(|value| value.checked_add(offset));
+            // The shifted values are still sorted
+            *self |= RoaringBitmap::from_sorted_iter(shifted).unwrap();
+            return;
+        }
+
+        for container in &other.containers {
+            let key = match container.key.checked_add(key_offset) {
+                Some(key) => key,
+                None => break,
+            };
+            match self.containers.binary_search_by_key(&key, |c| c.key) {
+                Ok(loc) => self.containers[loc] |= container,
+                Err(loc) => {
+                    let mut container = container.clone();
+                    container.key = key;
+                    self.containers.insert(loc, container);
+                }
+            }
+        }
+    }
+
+    /// Shifts every value by `delta_keys` multiples of 2^16, by rebasing the containers
+    /// on new keys.
+    ///
+    /// This is a key-granularity shift: it runs in O(containers) without looking at the
+    /// values, `delta_keys * 65536` is added to each of them. The containers that would be
+    /// shifted out of the `u32` range are dropped, their count is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb = RoaringBitmap::from([1, 2, 1 << 16, 3 << 16]);
+    ///
+    /// assert_eq!(rb.shift_containers(2), 0);
+    /// assert_eq!(rb, RoaringBitmap::from([(2 << 16) + 1, (2 << 16) + 2, 3 << 16, 5 << 16]));
+    ///
+    /// assert_eq!(rb.shift_containers(-3), 1);
+    /// assert_eq!(rb, RoaringBitmap::from([0, 2 << 16]));
+    /// ```
+    pub fn shift_containers(&mut self, delta_keys: i32) -> usize {
+        let before = self.containers.len();
+        self.containers.retain_mut(|container| {
+            match u16::try_from(i64::from(container.key) + i64::from(delta_keys)) {
+                Ok(key) => {
+                    container.key = key;
+                    true
+                }
+                Err(_) => false,
+            }
+        });
+        before - self.containers.len()
+    }
+
     /// Pushes `value` in the bitmap only if it is greater than the current maximum value.
     ///
     /// Returns whether the value was inserted.
@@ -212,15 +463,11 @@ impl RoaringBitmap {
     pub fn remove(&mut self, value: u32) -> bool {
         let (key, index) = util::split(value);
         match self.containers.binary_search_by_key(&key, |c| c.key) {
-            Ok(loc) => {
-                if self.containers[loc].remove(index) {
-                    if self.containers[loc].is_empty() {
-                        self.containers.remove(loc);
-                    }
-                    true
-                } else {
-                    false
+            Ok(loc) if self.containers[loc].remove(index) => {
+                if self.containers[loc].is_empty() {
+                    self.containers.remove(loc);
                 }
+                true
             }
             _ => false,
         }
@@ -270,6 +517,107 @@ impl RoaringBitmap {
         removed
     }
 
+    /// Retains only the values that fall in any of the given ranges.
+    ///
+    /// This is equivalent to intersecting with a bitmap built from the ranges,
+    /// without building it. The ranges can be unsorted and overlap each other.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb: RoaringBitmap = (0..100).collect();
+    /// rb.retain_ranges(&[90..=200, 10..=12]);
+    /// assert_eq!(rb.iter().collect::<Vec<u32>>(), [10, 11, 12, 90, 91, 92, 93, 94, 95, 96, 97, 98, 99]);
+    /// ```
+    pub fn retain_ranges(&mut self, ranges: &[RangeInclusive<u32>]) {
+        // Sort and merge the ranges so that the gaps between them can be removed
+        let mut sorted: Vec<(u32, u32)> =
+            ranges.iter().filter(|r| !r.is_empty()).map(|r| (*r.start(), *r.end())).collect();
+        sorted.sort_unstable();
+        let mut merged: Vec<(u32, u32)> = Vec::with_capacity(sorted.len());
+        for (start, end) in sorted {
+            match merged.last_mut() {
+                Some((_, last_end)) if start <= last_end.saturating_add(1) => {
+                    *last_end = (*last_end).max(end);
+                }
+                _ => merged.push((start, end)),
+            }
+        }
+
+        self.containers.retain_mut(|container| {
+            let lo = util::join(container.key, 0);
+            let hi = util::join(container.key, u16::MAX);
+
+            // The ranges overlapping this container, clamped to it
+            let first = merged.partition_point(|&(_, end)| end < lo);
+            let overlapping = merged[first..]
+                .iter()
+                .take_while(|&&(start, _)| start <= hi)
+                .map(|&(start, end)| (util::split(start.max(lo)).1, util::split(end.min(hi)).1));
+
+            // Remove every value between the overlapping ranges
+            let mut cursor = Some(0u16);
+            for (start, end) in overlapping {
+                if let Some(gap_start) = cursor.filter(|&gap_start| gap_start < start) {
+                    container.remove_range(gap_start..=start - 1);
+                }
+                cursor = end.checked_add(1);
+            }
+            if let Some(gap_start) = cursor {
+                container.remove_range(gap_start..=u16::MAX);
+            }
+
+            !container.is_empty()
+        });
+    }
+
+    /// Toggles a range of values: the values of the range that are present
+    /// are removed and the ones that are absent are inserted.
+    ///
+    /// Returns the net change in cardinality as a signed number, that is the
+    /// number of added values minus the number of removed values.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb = RoaringBitmap::new();
+    /// rb.insert(2);
+    /// assert_eq!(rb.toggle_range(0..4), 2);
+    /// assert_eq!(rb.iter().collect::<Vec<u32>>(), vec![0, 1, 3]);
+    /// assert_eq!(rb.toggle_range(0..2), -2);
+    /// assert_eq!(rb.iter().collect::<Vec<u32>>(), vec![3]);
+    /// ```
+    #[inline]
+    pub fn toggle_range<R>(&mut self, range: R) -> i64
+    where
+        R: RangeBounds<u32>,
+    {
+        let (start, end) = match util::convert_range_to_inclusive(range) {
+            Ok(range) => (*range.start(), *range.end()),
+            Err(_) => return 0,
+        };
+
+        let (start_container_key, start_index) = util::split(start);
+        let (end_container_key, end_index) = util::split(end);
+
+        let mut change = 0;
+        for key in start_container_key..=end_container_key {
+            let a = if key == start_container_key { start_index } else { 0 };
+            let b = if key == end_container_key { end_index } else { u16::MAX };
+
+            let index = self.find_container_by_key(key);
+            change += self.containers[index].flip_range(a..=b);
+            if self.containers[index].is_empty() {
+                self.containers.remove(index);
+            }
+        }
+        change
+    }
+
     /// Returns `true` if this set contains the specified integer.
     ///
     /// # Examples
@@ -292,6 +640,60 @@ impl RoaringBitmap {
         }
     }
 
+    /// Returns the values of `query` that are present in this set.
+    ///
+    /// This is the intersection of both sets, named after the intent of
+    /// filtering a set of candidates.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (1..100).collect();
+    /// let query = RoaringBitmap::from([0, 10, 99, 100]);
+    ///
+    /// assert_eq!(rb.membership_mask(&query), RoaringBitmap::from([10, 99]));
+    /// ```
+    pub fn membership_mask(&self, query: &RoaringBitmap) -> RoaringBitmap {
+        self & query
+    }
+
+    /// Returns, for each of the values, whether it is present in this set.
+    ///
+    /// Successive values belonging to the same container share a single
+    /// container lookup and are tested as a batch, sorting the values makes the
+    /// most of it. With the `simd` feature, the batches landing in a bitset
+    /// container gather the words and test the bits of several values at once.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (1..100).collect();
+    ///
+    /// assert_eq!(rb.membership_bools(&[0, 10, 99, 100]), vec![false, true, true, false]);
+    /// ```
+    pub fn membership_bools(&self, values: &[u32]) -> Vec<bool> {
+        let mut bools = Vec::with_capacity(values.len());
+        let mut values = values;
+
+        while let Some(&first) = values.first() {
+            let key = util::split(first).0;
+            let len = values.iter().position(|&value| util::split(value).0 != key);
+            let (batch, rest) = values.split_at(len.unwrap_or(values.len()));
+
+            match self.containers.binary_search_by_key(&key, |c| c.key) {
+                Ok(loc) => self.containers[loc].store.contains_batch(batch, &mut bools),
+                Err(_) => bools.resize(bools.len() + batch.len(), false),
+            }
+            values = rest;
+        }
+
+        bools
+    }
+
     /// Returns `true` if all values in the range are present in this set.
     ///
     /// # Examples
@@ -421,6 +823,9 @@ impl RoaringBitmap {
 
     /// Clears all integers in this set.
     ///
+    /// The capacity of the vector of containers is retained, refilling the bitmap afterward
+    /// doesn't need to grow it again. Use [`RoaringBitmap::clear_and_shrink`] to release it.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -437,6 +842,21 @@ impl RoaringBitmap {
         self.containers.clear();
     }
 
+    /// Clears all integers in this set and releases the memory it was using.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb: RoaringBitmap = (0..100_000).collect();
+    /// rb.clear_and_shrink();
+    /// assert!(rb.is_empty());
+    /// ```
+    pub fn clear_and_shrink(&mut self) {
+        self.containers = Vec::new();
+    }
+
     /// Returns `true` if there are no integers in this set.
     ///
     /// # Examples
@@ -532,6 +952,89 @@ impl RoaringBitmap {
         self.containers.last().and_then(|tail| tail.max().map(|max| util::join(tail.key, max)))
     }
 
+    /// Returns the number of bits needed to represent the set, one past its maximum value,
+    /// or 0 if the set is empty.
+    ///
+    /// This is the number of bits a plain bitmap such as the one read by
+    /// [`RoaringBitmap::from_lsb0_bytes`] needs to hold every value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb = RoaringBitmap::new();
+    /// assert_eq!(rb.bit_len(), 0);
+    ///
+    /// rb.insert(3);
+    /// rb.insert(4);
+    /// assert_eq!(rb.bit_len(), 5);
+    ///
+    /// rb.insert(u32::MAX);
+    /// assert_eq!(rb.bit_len(), 1 << 32);
+    /// ```
+    #[inline]
+    pub fn bit_len(&self) -> u64 {
+        self.max().map_or(0, |max| u64::from(max) + 1)
+    }
+
+    /// Returns `true` if all values in the set are strictly less than `bound`.
+    ///
+    /// Only the maximum value is checked, an empty set is always below the bound.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb = RoaringBitmap::new();
+    /// assert!(rb.max_below(0));
+    ///
+    /// rb.insert(3);
+    /// rb.insert(4);
+    /// assert!(rb.max_below(5));
+    /// assert!(!rb.max_below(4));
+    /// ```
+    #[inline]
+    pub fn max_below(&self, bound: u32) -> bool {
+        self.max().map_or(true, |max| max < bound)
+    }
+
+    /// Returns `true` if all values in the set are inside the range.
+    ///
+    /// Only the minimum and maximum values are checked, an empty set is always within the range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb = RoaringBitmap::new();
+    /// assert!(rb.within_range(7..7));
+    ///
+    /// rb.insert(3);
+    /// rb.insert(4);
+    /// assert!(rb.within_range(..));
+    /// assert!(rb.within_range(3..5));
+    /// assert!(!rb.within_range(3..4));
+    /// assert!(!rb.within_range(7..7));
+    /// ```
+    #[inline]
+    pub fn within_range<R>(&self, range: R) -> bool
+    where
+        R: RangeBounds<u32>,
+    {
+        let (min, max) = match (self.min(), self.max()) {
+            (Some(min), Some(max)) => (min, max),
+            _ => return true,
+        };
+        match util::convert_range_to_inclusive(range) {
+            Ok(range) => range.contains(&min) && range.contains(&max),
+            // Empty/Invalid ranges contain nothing
+            Err(_) => false,
+        }
+    }
+
     /// Returns the number of integers that are <= value. rank(u32::MAX) == len()
     ///
     /// # Examples
@@ -600,8 +1103,160 @@ impl RoaringBitmap {
         None
     }
 
+    /// Returns the `n`th integer in the set, assuming that `n < len()`.
+    ///
+    /// This is [`RoaringBitmap::select`] without the `Option`, for tight loops over ranks
+    /// that are known to be valid.
+    ///
+    /// # Panics
+    ///
+    /// When debug assertions are enabled and `n >= len()`. Otherwise the returned value is
+    /// unspecified, but it is never undefined behavior.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb = RoaringBitmap::from_iter([0, 10, 100]);
+    ///
+    /// assert_eq!(rb.value_at_unchecked(0), 0);
+    /// assert_eq!(rb.value_at_unchecked(1), 10);
+    /// assert_eq!(rb.value_at_unchecked(2), 100);
+    /// ```
+    #[inline]
+    pub fn value_at_unchecked(&self, n: u64) -> u32 {
+        debug_assert!(n < self.len(), "n must be lower than the len of the bitmap");
+        let mut n = n;
+
+        for container in &self.containers {
+            let len = container.len();
+            if len > n {
+                let index = container.store.select(n as u16).unwrap_or_default();
+                return util::join(container.key, index);
+            }
+            n -= len;
+        }
+
+        0
+    }
+
+    /// Returns the `count` integers of the set whose ranks start at `start_rank`,
+    /// in ascending order.
+    ///
+    /// The containers before `start_rank` are skipped at once, which is much faster
+    /// than calling [`RoaringBitmap::select`] for every rank. Fewer than `count`
+    /// integers are returned when `start_rank + count` exceeds [`RoaringBitmap::len`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb = RoaringBitmap::from_iter([0, 10, 100, 1000, 10000]);
+    ///
+    /// assert_eq!(rb.select_range(1, 3), vec![10, 100, 1000]);
+    /// assert_eq!(rb.select_range(3, 10), vec![1000, 10000]);
+    /// assert_eq!(rb.select_range(5, 10), vec![]);
+    /// ```
+    pub fn select_range(&self, start_rank: u64, count: u64) -> Vec<u32> {
+        let mut iter = self.iter();
+        if let Some(n) = start_rank.checked_sub(1) {
+            match usize::try_from(n) {
+                Ok(n) => iter.nth(n),
+                Err(_) => return Vec::new(),
+            };
+        }
+        iter.take(usize::try_from(count).unwrap_or(usize::MAX)).collect()
+    }
+
+    /// Counts the integers of the set in each of the buckets delimited by `boundaries`.
+    ///
+    /// The `n` boundaries define `n + 1` buckets: `..boundaries[0]`,
+    /// `boundaries[0]..boundaries[1]`, ..., `boundaries[n - 1]..`, the counts of all the
+    /// buckets sum up to [`RoaringBitmap::len`]. The counts are computed in a single forward
+    /// traversal of the containers, which is much faster than a
+    /// [`RoaringBitmap::range_cardinality`] call per bucket.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the boundaries are not sorted in ascending order, equal boundaries
+    /// delimit an empty bucket.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb = RoaringBitmap::from_iter([1, 2, 3, 10, 11, 100_000]);
+    ///
+    /// assert_eq!(rb.count_in_buckets(&[3, 10, 100]), vec![2, 1, 2, 1]);
+    /// assert_eq!(rb.count_in_buckets(&[5, 5]), vec![3, 0, 3]);
+    /// assert_eq!(rb.count_in_buckets(&[]), vec![6]);
+    /// ```
+    pub fn count_in_buckets(&self, boundaries: &[u32]) -> Vec<u64> {
+        assert!(
+            boundaries.windows(2).all(|pair| pair[0] <= pair[1]),
+            "boundaries must be sorted in ascending order"
+        );
+
+        let mut counts = Vec::with_capacity(boundaries.len() + 1);
+        let mut containers = self.containers.iter().peekable();
+        // The number of values in the containers before the current one
+        let mut before = 0;
+        // The number of values below the previous boundary
+        let mut previous = 0;
+
+        for &boundary in boundaries {
+            let (key, index) = util::split(boundary);
+            while let Some(container) = containers.next_if(|container| container.key < key) {
+                before += container.len();
+            }
+            let below = match containers.peek() {
+                Some(container) if container.key == key && index > 0 => {
+                    before + container.rank(index - 1)
+                }
+                _ => before,
+            };
+            counts.push(below - previous);
+            previous = below;
+        }
+
+        let len = before + containers.map(|container| container.len()).sum::<u64>();
+        counts.push(len - previous);
+        counts
+    }
+
+    /// Splits this set into two halves of the same cardinality, the lower
+    /// values and the upper ones.
+    ///
+    /// The split point is the value of rank `len() / 2`, which starts the upper
+    /// half. The halves are balanced by cardinality, not by the range of values
+    /// they cover. When the len is odd, the upper half has one more value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb = RoaringBitmap::from_iter([1, 2, 3, 1000, 2000]);
+    /// let (lower, upper) = rb.bisect_by_cardinality();
+    ///
+    /// assert_eq!(lower, RoaringBitmap::from_iter([1, 2]));
+    /// assert_eq!(upper, RoaringBitmap::from_iter([3, 1000, 2000]));
+    /// ```
+    pub fn bisect_by_cardinality(&self) -> (RoaringBitmap, RoaringBitmap) {
+        match self.select((self.len() / 2) as u32) {
+            Some(0) => (RoaringBitmap::new(), self.clone()),
+            Some(mid) => (self & (0..=mid - 1), self & (mid..=u32::MAX)),
+            None => (RoaringBitmap::new(), RoaringBitmap::new()),
+        }
+    }
+
     /// Removes the `n` smallests values from this bitmap.
     ///
+    /// The bitmap is cleared if `n` is greater than or equal to its len.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -614,6 +1269,10 @@ impl RoaringBitmap {
     /// let mut rb = RoaringBitmap::from_iter([1, 3, 7, 9]);
     /// rb.remove_smallest(2);
     /// assert_eq!(rb, RoaringBitmap::from_iter([7, 9]));
+    ///
+    /// rb.remove_smallest(u64::MAX);
+    /// assert!(rb.is_empty());
+    /// ```
     #[inline]
     pub fn remove_smallest(&mut self, mut n: u64) {
         // remove containers up to the front of the target
@@ -639,6 +1298,8 @@ impl RoaringBitmap {
 
     /// Removes the `n` biggests values from this bitmap.
     ///
+    /// The bitmap is cleared if `n` is greater than or equal to its len.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -649,6 +1310,10 @@ impl RoaringBitmap {
     /// assert_eq!(rb, RoaringBitmap::from_iter([1, 5]));
     /// rb.remove_biggest(1);
     /// assert_eq!(rb, RoaringBitmap::from_iter([1]));
+    ///
+    /// rb.remove_biggest(u64::MAX);
+    /// assert!(rb.is_empty());
+    /// ```
     #[inline]
     pub fn remove_biggest(&mut self, mut n: u64) {
         // remove containers up to the back of the target
@@ -695,8 +1360,78 @@ mod tests {
     use proptest::prelude::*;
 
     use super::*;
+    use crate::bitmap::container::ARRAY_LIMIT;
+    use crate::bitmap::store::Store;
 
     proptest! {
+        #[test]
+        fn removals_keep_the_smallest_store(
+            bitmap in RoaringBitmap::arbitrary(),
+            ranges in vec(0u32..=0x10_ffff, 0..8),
+            values in vec(0u32..=0x10_ffff, 0..100),
+            n in 0u64..100_000,
+        ){
+            fn assert_smallest_stores(bitmap: &RoaringBitmap) {
+                for container in &bitmap.containers {
+                    match &container.store {
+                        Store::Array(array) => assert!(array.len() <= ARRAY_LIMIT),
+                        Store::Bitmap(bits) => assert!(bits.len() > ARRAY_LIMIT),
+                    }
+                }
+            }
+
+            let mut b = bitmap.clone();
+            for range in ranges.chunks_exact(2) {
+                b.remove_range(range[0].min(range[1])..=range[0].max(range[1]));
+                assert_smallest_stores(&b);
+            }
+            for &value in &values {
+                b.remove(value);
+            }
+            assert_smallest_stores(&b);
+
+            let mut b = bitmap.clone();
+            b.remove_smallest(n);
+            assert_smallest_stores(&b);
+
+            let mut b = bitmap;
+            b.remove_biggest(n);
+            assert_smallest_stores(&b);
+        }
+
+        #[test]
+        fn rank_select_agree_across_stores(
+            bitmap in RoaringBitmap::arbitrary(),
+            values in vec(0u32..=0x10_ffff, 100),
+            ranks in vec(0u32..=300_000, 100),
+        ){
+            // The same set with every container stored as an array, then as a bitset
+            let mut arrays = bitmap.clone();
+            for container in &mut arrays.containers {
+                if let Store::Bitmap(bits) = &container.store {
+                    container.store = Store::Array(bits.to_array_store());
+                }
+            }
+            let mut bitsets = bitmap.clone();
+            for container in &mut bitsets.containers {
+                container.store = container.store.to_bitmap();
+            }
+
+            for value in values.into_iter().chain(bitmap.iter().step_by(97)) {
+                let rank = bitmap.rank(value);
+                prop_assert_eq!(arrays.rank(value), rank);
+                prop_assert_eq!(bitsets.rank(value), rank);
+            }
+            for n in ranks.into_iter().chain([0, bitmap.len().saturating_sub(1) as u32]) {
+                let selected = bitmap.select(n);
+                prop_assert_eq!(arrays.select(n), selected);
+                prop_assert_eq!(bitsets.select(n), selected);
+                if let Some(value) = selected {
+                    prop_assert_eq!(bitsets.rank(value), u64::from(n) + 1);
+                }
+            }
+        }
+
         #[test]
         fn insert_range(
             lo in 0u32..=65535, hi in 65536u32..=131071,
@@ -726,6 +1461,199 @@ mod tests {
                 );
             }
         }
+
+        #[test]
+        fn select_range(
+            bitmap in RoaringBitmap::arbitrary(),
+            start_rank in 0u64..=10_000, count in 0u64..=10_000,
+        ){
+            let expected: Vec<u32> = (start_rank..start_rank + count)
+                .map_while(|rank| bitmap.select(rank as u32))
+                .collect();
+            prop_assert_eq!(bitmap.select_range(start_rank, count), expected);
+        }
+
+        #[test]
+        fn count_in_buckets(
+            bitmap in RoaringBitmap::arbitrary(),
+            boundaries in vec(prop_oneof![0u32..=262143, Just(0), Just(u32::MAX)], 0..10),
+        ){
+            let mut boundaries = boundaries;
+            boundaries.sort_unstable();
+
+            let mut expected = Vec::new();
+            let mut start = 0;
+            for &boundary in &boundaries {
+                expected.push(bitmap.range_cardinality(start..boundary));
+                start = boundary;
+            }
+            expected.push(bitmap.range_cardinality(start..));
+            prop_assert_eq!(bitmap.count_in_buckets(&boundaries), expected);
+        }
+
+        #[test]
+        fn shift_containers(
+            bitmap in RoaringBitmap::arbitrary(),
+            delta_keys in prop_oneof![-20i32..=20, Just(i32::MIN), Just(i32::MAX)],
+        ){
+            let delta = i64::from(delta_keys) << 16;
+            let expected: RoaringBitmap = bitmap
+                .iter()
+                .filter_map(|value| u32::try_from(i64::from(value) + delta).ok())
+                .collect();
+            let dropped = bitmap.containers.iter()
+                .filter(|container| u16::try_from(i64::from(container.key) + i64::from(delta_keys)).is_err())
+                .count();
+
+            let mut shifted = bitmap;
+            prop_assert_eq!(shifted.shift_containers(delta_keys), dropped);
+            prop_assert_eq!(shifted, expected);
+        }
+
+        #[test]
+        fn retain_ranges(
+            bitmap in RoaringBitmap::arbitrary(),
+            ranges in vec((0u32..=262143, 0u32..=70000), 0..5),
+        ){
+            let ranges: Vec<_> = ranges.into_iter().map(|(start, len)| start..=start + len).collect();
+            let mut expected = RoaringBitmap::new();
+            for range in &ranges {
+                expected.insert_range(range.clone());
+            }
+            expected &= &bitmap;
+
+            let mut b = bitmap;
+            b.retain_ranges(&ranges);
+            prop_assert_eq!(b, expected);
+        }
+
+        #[test]
+        fn extend_ranges(
+            bitmap in RoaringBitmap::arbitrary(),
+            ranges in vec((0u32..=262143, 0u32..=262143), 0..10),
+        ){
+            // Reversed ranges are empty
+            let ranges: Vec<_> = ranges.into_iter().map(|(start, end)| start..=end).collect();
+            let mut expected = bitmap.clone();
+            for range in &ranges {
+                expected.insert_range(range.clone());
+            }
+
+            let mut b = bitmap.clone();
+            let inserted = b.extend_ranges(ranges);
+            prop_assert_eq!(inserted, expected.len() - bitmap.len());
+            prop_assert_eq!(b, expected);
+        }
+
+        #[test]
+        fn within_range(
+            bitmap in RoaringBitmap::arbitrary(),
+            start in 0u32..=0x11_0000,
+            end in 0u32..=0x11_0000,
+        ){
+            prop_assert_eq!(bitmap.within_range(start..end), bitmap.iter().all(|v| (start..end).contains(&v)));
+            prop_assert_eq!(bitmap.within_range(start..), bitmap.iter().all(|v| v >= start));
+            prop_assert_eq!(bitmap.max_below(end), bitmap.iter().all(|v| v < end));
+        }
+
+        #[test]
+        fn insert_shifted(
+            bitmap in RoaringBitmap::arbitrary(),
+            other in RoaringBitmap::arbitrary(),
+            offset in prop_oneof![any::<u16>().prop_map(|key| u32::from(key) << 16), any::<u32>()],
+        ){
+            let mut expected = bitmap.clone();
+            for value in &other {
+                if let Some(value) = value.checked_add(offset) {
+                    expected.insert(value);
+                }
+            }
+
+            let mut b = bitmap;
+            b.insert_shifted(&other, offset);
+            prop_assert_eq!(b, expected);
+        }
+
+        #[test]
+        fn toggle_range(
+            bitmap in RoaringBitmap::arbitrary(),
+            lo in 0u32..=262143, len in 0u32..=131072,
+        ){
+            let range = lo..lo + len;
+            let toggled: RoaringBitmap = range.clone().collect();
+
+            let mut b = bitmap.clone();
+            let change = b.toggle_range(range);
+            let expected = &bitmap ^ &toggled;
+
+            prop_assert_eq!(change, expected.len() as i64 - bitmap.len() as i64);
+            prop_assert_eq!(b, expected);
+        }
+    }
+
+    #[test]
+    fn test_retain_ranges_bounds() {
+        let original: RoaringBitmap =
+            [0, 1, 65535, 65536, u32::MAX - 1, u32::MAX].into_iter().collect();
+
+        let mut b = original.clone();
+        b.retain_ranges(&[0..=u32::MAX]);
+        assert_eq!(b, original);
+
+        let mut b = original.clone();
+        let empty = RangeInclusive::new(10, 5);
+        b.retain_ranges(&[u32::MAX..=u32::MAX, 1..=65535, empty]);
+        assert_eq!(b, RoaringBitmap::from([1, 65535, u32::MAX]));
+
+        let mut b = original;
+        b.retain_ranges(&[]);
+        assert!(b.is_empty());
+        assert!(b.containers.is_empty());
+    }
+
+    #[test]
+    fn test_extend_ranges_bounds() {
+        let mut b = RoaringBitmap::new();
+        let empty = RangeInclusive::new(10, 5);
+        assert_eq!(
+            b.extend_ranges([empty, u32::MAX..=u32::MAX, 0..=0, u32::MAX - 1..=u32::MAX]),
+            3
+        );
+        assert_eq!(b, RoaringBitmap::from([0, u32::MAX - 1, u32::MAX]));
+
+        assert_eq!(b.extend_ranges([]), 0);
+        assert_eq!(b.extend_ranges([65535..=65536, 0..=65535]), 65536);
+        assert_eq!(b.len(), 65537 + 2);
+    }
+
+    #[test]
+    fn test_toggle_range_containers() {
+        let mut b = RoaringBitmap::new();
+        assert_eq!(b.toggle_range(10..=70_000), 69_991);
+        assert_eq!(b.len(), 69_991);
+        assert_eq!(b.toggle_range(0..=u32::MAX), (1 << 32) - 2 * 69_991);
+        assert_eq!(b.toggle_range(..), 2 * 69_991 - (1 << 32));
+        assert_eq!(b.toggle_range(10..=70_000), -69_991);
+        assert!(b.is_empty());
+        assert!(b.containers.is_empty());
+        assert_eq!(b.toggle_range(5..5), 0);
+    }
+
+    #[test]
+    fn test_remove_range_downgrades_to_array() {
+        let mut b = RoaringBitmap::new();
+        b.insert_range(0..65536);
+        assert!(matches!(b.containers[0].store, Store::Bitmap(_)));
+
+        // Keep exactly `ARRAY_LIMIT` values
+        assert_eq!(b.remove_range(4096..65536), 65536 - 4096);
+        assert!(matches!(b.containers[0].store, Store::Array(_)));
+        assert_eq!(b.len(), 4096);
+
+        b.insert(4096);
+        assert!(matches!(b.containers[0].store, Store::Bitmap(_)));
+        b.remove(0);
+        assert!(matches!(b.containers[0].store, Store::Array(_)));
     }
 
     #[test]
@@ -945,4 +1873,36 @@ mod tests {
         bitmap.remove_biggest(4);
         assert_eq!(bitmap, RoaringBitmap::default());
     }
+
+    #[test]
+    fn remove_more_than_len() {
+        for bitmap in [RoaringBitmap::from_iter(0..10), RoaringBitmap::from_iter(0..100_000)] {
+            for n in [bitmap.len(), bitmap.len() + 1, u64::MAX] {
+                let mut b = bitmap.clone();
+                b.remove_smallest(n);
+                assert!(b.is_empty());
+
+                let mut b = bitmap.clone();
+                b.remove_biggest(n);
+                assert!(b.is_empty());
+            }
+        }
+
+        // The containers and stores also clamp `n` by themselves
+        for values in [0..10, 0..10_000] {
+            let mut container = RoaringBitmap::from_iter(values.clone()).containers.remove(0);
+            container.remove_smallest(u64::MAX);
+            assert!(container.is_empty());
+
+            let mut container = RoaringBitmap::from_iter(values).containers.remove(0);
+            container.remove_biggest(u64::MAX);
+            assert!(container.is_empty());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "sorted")]
+    fn count_in_buckets_unsorted() {
+        RoaringBitmap::from([1, 2]).count_in_buckets(&[2, 1]);
+    }
 }
@@ -1,6 +1,6 @@
 use alloc::vec;
-use core::iter::FusedIterator;
-use core::ops::RangeBounds;
+use core::iter::{self, FusedIterator};
+use core::ops::{Add, RangeBounds, RangeInclusive};
 use core::slice;
 
 use super::container::Container;
@@ -26,6 +26,47 @@ pub struct IntoIter {
     back: Option<container::Iter<'static>>,
 }
 
+/// A draining iterator for `RoaringBitmap`.
+///
+/// This `struct` is created by [`RoaringBitmap::drain`] and [`RoaringBitmap::drain_range`].
+pub struct Drain<'a> {
+    bitmap: &'a mut RoaringBitmap,
+    // The values yielded so far are in `start..next`
+    start: u64,
+    next: u64,
+    end: u64,
+    // The index of the container of the next value and the cursor in its store
+    container: usize,
+    cursor: usize,
+}
+
+/// An iterator over the maximal runs of consecutive values of a `RoaringBitmap`.
+///
+/// This `struct` is created by [`RoaringBitmap::maximal_runs`].
+#[derive(Clone)]
+pub struct MaximalRuns<'a> {
+    runs: MergeRuns<ContainersRuns<'a>, u32>,
+}
+
+type ContainersRuns<'a> =
+    iter::FlatMap<slice::Iter<'a, Container>, container::Runs<'a>, ContainerRunsFn<'a>>;
+type ContainerRunsFn<'a> = fn(&'a Container) -> container::Runs<'a>;
+
+/// Merges the runs of an iterator of ascending and disjoint runs that are adjacent,
+/// a run is never followed by a run starting right after its end.
+#[derive(Clone)]
+pub(crate) struct MergeRuns<I, T> {
+    runs: I,
+    // The last run, kept until we know it isn't continued by the next one
+    pending: Option<RangeInclusive<T>>,
+}
+
+impl<I, T> MergeRuns<I, T> {
+    pub(crate) fn new(runs: I) -> MergeRuns<I, T> {
+        MergeRuns { runs, pending: None }
+    }
+}
+
 #[inline]
 fn and_then_or_clear<T, U>(opt: &mut Option<T>, f: impl FnOnce(&mut T) -> Option<U>) -> Option<U> {
     let x = f(opt.as_mut()?);
@@ -151,8 +192,61 @@ fn advance_back_to_impl<'a, It>(
     }
 }
 
+fn peek_impl(
+    front: &Option<container::Iter<'_>>,
+    containers: &impl AsRef<[Container]>,
+    back: &Option<container::Iter<'_>>,
+) -> Option<u32> {
+    front
+        .as_ref()
+        .and_then(container::Iter::peek)
+        .or_else(|| containers.as_ref().first().and_then(|c| c.into_iter().peek()))
+        .or_else(|| back.as_ref().and_then(container::Iter::peek))
+}
+
+fn peek_back_impl(
+    front: &Option<container::Iter<'_>>,
+    containers: &impl AsRef<[Container]>,
+    back: &Option<container::Iter<'_>>,
+) -> Option<u32> {
+    back.as_ref()
+        .and_then(container::Iter::peek_back)
+        .or_else(|| containers.as_ref().last().and_then(|c| c.into_iter().peek_back()))
+        .or_else(|| front.as_ref().and_then(container::Iter::peek_back))
+}
+
+fn fill_buffer_impl<'a, It>(
+    buf: &mut Vec<u32>,
+    max: usize,
+    front: &mut Option<container::Iter<'a>>,
+    containers: &mut It,
+    back: &mut Option<container::Iter<'a>>,
+) -> usize
+where
+    It: Iterator,
+    It::Item: IntoIterator<IntoIter = container::Iter<'a>>,
+{
+    let mut pushed = 0;
+    loop {
+        if let Some(iter) = front {
+            pushed += iter.fill_buffer(buf, max - pushed);
+            if pushed == max {
+                return pushed;
+            }
+        }
+        *front = match containers.next() {
+            Some(container) => Some(container.into_iter()),
+            None => break,
+        };
+    }
+    if let Some(iter) = back {
+        pushed += iter.fill_buffer(buf, max - pushed);
+    }
+    pushed
+}
+
 impl Iter<'_> {
-    fn new(containers: &[Container]) -> Iter {
+    fn new(containers: &[Container]) -> Iter<'_> {
         Iter { front: None, containers: containers.iter(), back: None }
     }
 
@@ -162,6 +256,9 @@ impl Iter<'_> {
 
     /// Advance the iterator to the first position where the item has a value >= `n`
     ///
+    /// The containers before `n` are skipped by binary search, and bitmap containers
+    /// jump directly to the word containing `n`, so large strides are cheap.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -181,6 +278,8 @@ impl Iter<'_> {
 
     /// Advance the back of the iterator to the first position where the item has a value <= `n`
     ///
+    /// The containers after `n` are skipped by binary search, like in `advance_to`.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -197,6 +296,67 @@ impl Iter<'_> {
     pub fn advance_back_to(&mut self, n: u32) {
         advance_back_to_impl(n, &mut self.front, &mut self.containers, &mut self.back);
     }
+
+    /// Returns the next value of the iterator without consuming it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let bitmap = (1..3).collect::<RoaringBitmap>();
+    /// let mut iter = bitmap.iter();
+    ///
+    /// assert_eq!(iter.peek(), Some(1));
+    /// assert_eq!(iter.next(), Some(1));
+    /// iter.advance_to(3);
+    /// assert_eq!(iter.peek(), None);
+    /// ```
+    pub fn peek(&self) -> Option<u32> {
+        peek_impl(&self.front, &self.containers, &self.back)
+    }
+
+    /// Returns the next value from the back of the iterator without consuming it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let bitmap = (1..3).collect::<RoaringBitmap>();
+    /// let mut iter = bitmap.iter();
+    ///
+    /// assert_eq!(iter.peek_back(), Some(2));
+    /// assert_eq!(iter.next_back(), Some(2));
+    /// assert_eq!(iter.peek(), Some(1));
+    /// ```
+    pub fn peek_back(&self) -> Option<u32> {
+        peek_back_impl(&self.front, &self.containers, &self.back)
+    }
+
+    /// Pushes up to `max` values of the iterator to `buf` and returns the number of
+    /// values pushed, fewer than `max` only when the iterator is exhausted.
+    ///
+    /// The values are copied a container at a time, and full words of bitmap containers
+    /// as whole ranges, which is cheaper than calling `next` for each value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let bitmap = (1..5).collect::<RoaringBitmap>();
+    /// let mut iter = bitmap.iter();
+    /// let mut buf = Vec::new();
+    ///
+    /// assert_eq!(iter.fill_buffer(&mut buf, 3), 3);
+    /// assert_eq!(iter.fill_buffer(&mut buf, 3), 1);
+    /// assert_eq!(buf, [1, 2, 3, 4]);
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn fill_buffer(&mut self, buf: &mut Vec<u32>, max: usize) -> usize {
+        fill_buffer_impl(buf, max, &mut self.front, &mut self.containers, &mut self.back)
+    }
 }
 
 impl IntoIter {
@@ -210,6 +370,9 @@ impl IntoIter {
 
     /// Advance the iterator to the first position where the item has a value >= `n`
     ///
+    /// The containers before `n` are skipped by binary search, and bitmap containers
+    /// jump directly to the word containing `n`, so large strides are cheap.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -217,7 +380,7 @@ impl IntoIter {
     /// use core::iter::FromIterator;
     ///
     /// let bitmap = (1..3).collect::<RoaringBitmap>();
-    /// let mut iter = bitmap.iter();
+    /// let mut iter = bitmap.into_iter();
     /// iter.advance_to(2);
     ///
     /// assert_eq!(iter.next(), Some(2));
@@ -229,6 +392,8 @@ impl IntoIter {
 
     /// Advance the back of the iterator to the first position where the item has a value <= `n`
     ///
+    /// The containers after `n` are skipped by binary search, like in `advance_to`.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -245,6 +410,67 @@ impl IntoIter {
     pub fn advance_back_to(&mut self, n: u32) {
         advance_back_to_impl(n, &mut self.front, &mut self.containers, &mut self.back);
     }
+
+    /// Returns the next value of the iterator without consuming it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let bitmap = (1..3).collect::<RoaringBitmap>();
+    /// let mut iter = bitmap.into_iter();
+    ///
+    /// assert_eq!(iter.peek(), Some(1));
+    /// assert_eq!(iter.next(), Some(1));
+    /// iter.advance_to(3);
+    /// assert_eq!(iter.peek(), None);
+    /// ```
+    pub fn peek(&self) -> Option<u32> {
+        peek_impl(&self.front, &self.containers, &self.back)
+    }
+
+    /// Returns the next value from the back of the iterator without consuming it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let bitmap = (1..3).collect::<RoaringBitmap>();
+    /// let mut iter = bitmap.into_iter();
+    ///
+    /// assert_eq!(iter.peek_back(), Some(2));
+    /// assert_eq!(iter.next_back(), Some(2));
+    /// assert_eq!(iter.peek(), Some(1));
+    /// ```
+    pub fn peek_back(&self) -> Option<u32> {
+        peek_back_impl(&self.front, &self.containers, &self.back)
+    }
+
+    /// Pushes up to `max` values of the iterator to `buf` and returns the number of
+    /// values pushed, fewer than `max` only when the iterator is exhausted.
+    ///
+    /// The values are copied a container at a time, and full words of bitmap containers
+    /// as whole ranges, which is cheaper than calling `next` for each value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let bitmap = (1..5).collect::<RoaringBitmap>();
+    /// let mut iter = bitmap.into_iter();
+    /// let mut buf = Vec::new();
+    ///
+    /// assert_eq!(iter.fill_buffer(&mut buf, 3), 3);
+    /// assert_eq!(iter.fill_buffer(&mut buf, 3), 1);
+    /// assert_eq!(buf, [1, 2, 3, 4]);
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn fill_buffer(&mut self, buf: &mut Vec<u32>, max: usize) -> usize {
+        fill_buffer_impl(buf, max, &mut self.front, &mut self.containers, &mut self.back)
+    }
 }
 
 fn size_hint_impl(
@@ -542,9 +768,81 @@ impl DoubleEndedIterator for IntoIter {
 impl ExactSizeIterator for IntoIter {}
 impl FusedIterator for IntoIter {}
 
+impl Iterator for Drain<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.next >= self.end {
+            return None;
+        }
+        while let Some(container) = self.bitmap.containers.get(self.container) {
+            if let Some(index) = container.store.next_at(&mut self.cursor) {
+                let value = util::join(container.key, index);
+                if u64::from(value) < self.end {
+                    self.next = u64::from(value) + 1;
+                    return Some(value);
+                }
+                break;
+            }
+            self.container += 1;
+            self.cursor = 0;
+        }
+        // There are no more values in the range, they will all be removed
+        self.next = self.end;
+        None
+    }
+}
+
+impl FusedIterator for Drain<'_> {}
+
+impl Drop for Drain<'_> {
+    fn drop(&mut self) {
+        if self.start < self.next {
+            self.bitmap.remove_range(self.start as u32..=(self.next - 1) as u32);
+        }
+    }
+}
+
+impl<I, T> Iterator for MergeRuns<I, T>
+where
+    I: Iterator<Item = RangeInclusive<T>>,
+    T: Copy + PartialEq + Add<Output = T> + From<u8>,
+{
+    type Item = RangeInclusive<T>;
+
+    fn next(&mut self) -> Option<RangeInclusive<T>> {
+        let mut pending = self.pending.take().or_else(|| self.runs.next())?;
+        for run in self.runs.by_ref() {
+            // Only the last run can end at the maximum value
+            if *pending.end() + T::from(1) == *run.start() {
+                pending = *pending.start()..=*run.end();
+            } else {
+                self.pending = Some(run);
+                break;
+            }
+        }
+        Some(pending)
+    }
+}
+
+impl<I, T> FusedIterator for MergeRuns<I, T> where Self: Iterator {}
+
+impl Iterator for MaximalRuns<'_> {
+    type Item = RangeInclusive<u32>;
+
+    fn next(&mut self) -> Option<RangeInclusive<u32>> {
+        self.runs.next()
+    }
+}
+
+impl FusedIterator for MaximalRuns<'_> {}
+
 impl RoaringBitmap {
     /// Iterator over each value stored in the RoaringBitmap, guarantees values are ordered by value.
     ///
+    /// Values are yielded in strictly ascending order, and in strictly descending order
+    /// when the iterator is reversed with [`Iterator::rev`].
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -558,7 +856,7 @@ impl RoaringBitmap {
     /// assert_eq!(iter.next(), Some(2));
     /// assert_eq!(iter.next(), None);
     /// ```
-    pub fn iter(&self) -> Iter {
+    pub fn iter(&self) -> Iter<'_> {
         Iter::new(&self.containers)
     }
 
@@ -616,6 +914,54 @@ impl RoaringBitmap {
         iter
     }
 
+    /// Iterator over every value of a range, in ascending order, along with whether it is in
+    /// the set.
+    ///
+    /// The set values are walked once, alongside the range: each position is only compared
+    /// with the next set value, without looking up its container. The cost is proportional to
+    /// the length of the range, in sparse regions and empty containers most positions yield
+    /// `false` for a single comparison. To only visit the set values prefer
+    /// [`RoaringBitmap::range`], or [`RoaringBitmap::fold_runs`] for dense regions.
+    ///
+    /// # Panics
+    ///
+    /// Panics on the same ranges as [`RoaringBitmap::range`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let bitmap = RoaringBitmap::from([1, 2, 5, 100_000]);
+    /// let mut iter = bitmap.dense_iter(0..4);
+    ///
+    /// assert_eq!(iter.next(), Some((0, false)));
+    /// assert_eq!(iter.next(), Some((1, true)));
+    /// assert_eq!(iter.next(), Some((2, true)));
+    /// assert_eq!(iter.next(), Some((3, false)));
+    /// assert_eq!(iter.next(), None);
+    ///
+    /// assert_eq!(bitmap.dense_iter(99_999..=100_001).filter(|&(_, present)| present).count(), 1);
+    /// ```
+    pub fn dense_iter<R>(&self, range: R) -> impl Iterator<Item = (u32, bool)> + '_
+    where
+        R: RangeBounds<u32>,
+    {
+        let range = match util::convert_range_to_inclusive(range) {
+            Ok(range) => Some(range),
+            Err(util::ConvertRangeError::Empty) => None,
+            Err(util::ConvertRangeError::StartGreaterThanEnd) => {
+                panic!("range start is greater than range end")
+            }
+            Err(util::ConvertRangeError::StartAndEndEqualExcluded) => {
+                panic!("range start and end are equal and excluded")
+            }
+        };
+        let mut values =
+            range.clone().map_or_else(Iter::empty, |range| self.range(range)).peekable();
+        range.into_iter().flatten().map(move |value| (value, values.next_if_eq(&value).is_some()))
+    }
+
     /// Iterator over values within a range stored in the RoaringBitmap.
     ///
     /// # Examples
@@ -672,6 +1018,129 @@ impl RoaringBitmap {
         }
         iter
     }
+
+    /// Folds over the maximal runs of consecutive values of the set, in ascending order.
+    ///
+    /// The runs are merged across container boundaries, a run is never followed by a run
+    /// starting right after its end. This is much faster than iterating over dense sets value
+    /// by value, e.g. to compute the sum of the values.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb = RoaringBitmap::from_iter((1..=3).chain(65530..=65540).chain([u32::MAX]));
+    ///
+    /// let runs = rb.fold_runs(Vec::new(), |mut runs, run| {
+    ///     runs.push(run);
+    ///     runs
+    /// });
+    /// assert_eq!(runs, vec![1..=3, 65530..=65540, u32::MAX..=u32::MAX]);
+    ///
+    /// let sum = rb.fold_runs(0u64, |sum, run| {
+    ///     let (start, end) = (u64::from(*run.start()), u64::from(*run.end()));
+    ///     sum + (start + end) * (end - start + 1) / 2
+    /// });
+    /// assert_eq!(sum, rb.iter().map(u64::from).sum());
+    /// ```
+    pub fn fold_runs<B, F>(&self, init: B, f: F) -> B
+    where
+        F: FnMut(B, RangeInclusive<u32>) -> B,
+    {
+        self.maximal_runs().fold(init, f)
+    }
+
+    /// Iterates over the maximal runs of consecutive values, in ascending order.
+    ///
+    /// This is the iterator counterpart of [`RoaringBitmap::fold_runs`]: the runs spanning
+    /// several containers are stitched together, a run is never followed by a run starting
+    /// right after its end.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb = RoaringBitmap::from_iter((1..=3).chain(65530..=65540).chain([u32::MAX]));
+    ///
+    /// let mut runs = rb.maximal_runs();
+    /// assert_eq!(runs.next(), Some(1..=3));
+    /// assert_eq!(runs.next(), Some(65530..=65540));
+    /// assert_eq!(runs.next(), Some(u32::MAX..=u32::MAX));
+    /// assert_eq!(runs.next(), None);
+    /// ```
+    pub fn maximal_runs(&self) -> MaximalRuns<'_> {
+        let runs: ContainersRuns = self.containers.iter().flat_map(Container::runs);
+        MaximalRuns { runs: MergeRuns::new(runs) }
+    }
+
+    /// Removes the values from the set as they are yielded, in ascending order.
+    ///
+    /// Unlike [`Vec::drain`], only the values yielded by the iterator are removed: if it is
+    /// dropped before the end, the remaining values are kept in the set. The values are
+    /// removed when the iterator is dropped, if it is leaked they stay in the set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb = RoaringBitmap::from([1, 2, 3, 4]);
+    ///
+    /// let mut drain = rb.drain();
+    /// assert_eq!(drain.next(), Some(1));
+    /// assert_eq!(drain.next(), Some(2));
+    /// drop(drain);
+    /// assert_eq!(rb, RoaringBitmap::from([3, 4]));
+    ///
+    /// assert!(rb.drain().eq([3, 4]));
+    /// assert!(rb.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_> {
+        self.drain_range(..)
+    }
+
+    /// Removes the values within a range from the set as they are yielded, in ascending order.
+    ///
+    /// Only the values yielded by the iterator are removed, see [`RoaringBitmap::drain`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is invalid, like [`RoaringBitmap::range`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb = RoaringBitmap::from([0, 1, 2, 3, 4, 5, 10, 11, 12, 20, 21, u32::MAX]);
+    ///
+    /// assert!(rb.drain_range(10..20).eq([10, 11, 12]));
+    /// assert_eq!(rb.drain_range(4..).next(), Some(4));
+    /// assert_eq!(rb, RoaringBitmap::from([0, 1, 2, 3, 5, 20, 21, u32::MAX]));
+    /// ```
+    pub fn drain_range<R>(&mut self, range: R) -> Drain<'_>
+    where
+        R: RangeBounds<u32>,
+    {
+        let (start, end) = match util::convert_range_to_inclusive(range) {
+            Ok(range) => (u64::from(*range.start()), u64::from(*range.end()) + 1),
+            Err(util::ConvertRangeError::Empty) => (0, 0),
+            Err(util::ConvertRangeError::StartGreaterThanEnd) => {
+                panic!("range start is greater than range end")
+            }
+            Err(util::ConvertRangeError::StartAndEndEqualExcluded) => {
+                panic!("range start and end are equal and excluded")
+            }
+        };
+        let (key, index) = util::split(start as u32);
+        let (container, cursor) = match self.containers.binary_search_by_key(&key, |c| c.key) {
+            Ok(loc) => (loc, self.containers[loc].store.cursor_at(index)),
+            Err(loc) => (loc, 0),
+        };
+        Drain { bitmap: self, start, next: start, end, container, cursor }
+    }
 }
 
 impl<'a> IntoIterator for &'a RoaringBitmap {
@@ -719,7 +1188,9 @@ impl Extend<u32> for RoaringBitmap {
     /// This is expected to be faster than calling [`RoaringBitmap::insert`] on each value.
     ///
     /// The provided integers values don't have to be in sorted order, but it may be preferable
-    /// to sort them from a performance point of view.
+    /// to sort them from a performance point of view: the values are pushed at the end of the
+    /// bitmap as long as they are greater than its max, the first smaller value falls back to
+    /// inserting the rest of the values.
     ///
     /// # Examples
     ///
@@ -734,7 +1205,15 @@ impl Extend<u32> for RoaringBitmap {
     /// ```
     #[inline]
     fn extend<I: IntoIterator<Item = u32>>(&mut self, values: I) {
-        let mut values = values.into_iter();
+        let mut values = values.into_iter().peekable();
+
+        // A `None` max is smaller than any value
+        let mut max = self.max();
+        while let Some(value) = values.next_if(|&value| max < Some(value)) {
+            self.push_unchecked(value);
+            max = Some(value);
+        }
+
         let value = match values.next() {
             Some(value) => value,
             None => return,
@@ -821,6 +1300,32 @@ impl RoaringBitmap {
         rb.append(iterator).map(|_| rb)
     }
 
+    /// Create the set from an iterator, reserving room for the containers of every value up to
+    /// `expected_max` beforehand.
+    ///
+    /// The hint only avoids growing the vector of containers as values are inserted, it has no
+    /// effect on the content of the set: values greater than `expected_max` are still inserted.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb = RoaringBitmap::from_iter_with_capacity((0..10).map(|i| i << 16), 9 << 16);
+    ///
+    /// assert_eq!(rb.len(), 10);
+    /// assert!(rb.iter().eq((0..10).map(|i| i << 16)));
+    /// ```
+    pub fn from_iter_with_capacity<I: IntoIterator<Item = u32>>(
+        iterator: I,
+        expected_max: u32,
+    ) -> RoaringBitmap {
+        let (key, _) = util::split(expected_max);
+        let mut rb = RoaringBitmap { containers: Vec::with_capacity(usize::from(key) + 1) };
+        rb.extend(iterator);
+        rb
+    }
+
     /// Extend the set with a sorted iterator.
     ///
     /// The values of the iterator must be ordered and strictly greater than the greatest value
@@ -6,24 +6,39 @@ mod proptests;
 mod statistics;
 mod store;
 mod util;
+mod validation;
 
 // Order of these modules matters as it determines the `impl` blocks order in
 // the docs
+#[cfg(feature = "bit-vec")]
+mod bit_vec;
 mod cmp;
 mod inherent;
 mod iter;
 mod ops;
 #[cfg(feature = "std")]
 mod ops_with_serialized;
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "rkyv")]
+mod rkyv;
 #[cfg(feature = "serde")]
 mod serde;
 #[cfg(feature = "std")]
 pub(crate) mod serialization;
 
 use self::cmp::Pairs;
+pub use self::iter::Drain;
 pub use self::iter::IntoIter;
 pub use self::iter::Iter;
-pub use self::statistics::Statistics;
+pub use self::iter::MaximalRuns;
+pub(crate) use self::iter::MergeRuns;
+#[cfg(feature = "serde")]
+pub use self::serde::serde_as_seq;
+#[cfg(feature = "std")]
+pub use self::serialization::{SerializeError, SerializedSizeBreakdown};
+pub use self::statistics::{ContainerInfo, ContainerKind, InsertOutcome, Statistics};
+pub use self::validation::{Invariant, ValidationError};
 
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
@@ -45,6 +60,11 @@ use alloc::vec::Vec;
 /// println!("total bits set to true: {}", rb.len());
 /// ```
 #[derive(PartialEq)]
+#[cfg_attr(feature = "rkyv", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    archive_attr(doc = "An archived [`RoaringBitmap`], see the `rkyv` feature of the crate.")
+)]
 pub struct RoaringBitmap {
     containers: Vec<container::Container>,
 }
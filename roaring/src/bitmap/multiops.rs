@@ -114,6 +114,91 @@ where
     }
 }
 
+impl RoaringBitmap {
+    /// Unions in-place all the given bitmaps into `self`.
+    ///
+    /// This is the in-place counterpart of [`MultiOps::union`]: `self` is used as the
+    /// accumulator and the containers of the operands that don't intersect with it are
+    /// moved rather than copied.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb: RoaringBitmap = (1..4).collect();
+    /// rb.union_all([(3..6).collect(), (100_000..100_002).collect()]);
+    ///
+    /// assert_eq!(rb, RoaringBitmap::from([1, 2, 3, 4, 5, 100_000, 100_001]));
+    /// ```
+    pub fn union_all<I: IntoIterator<Item = RoaringBitmap>>(&mut self, others: I) {
+        for bitmap in others {
+            merge_container_owned(
+                &mut self.containers,
+                bitmap.containers,
+                BitOrAssign::bitor_assign,
+            );
+        }
+
+        self.containers.retain_mut(|container| {
+            if !container.is_empty() {
+                container.ensure_correct_store();
+                true
+            } else {
+                false
+            }
+        });
+    }
+
+    /// Intersects in-place `self` with all the given bitmaps.
+    ///
+    /// This is the in-place counterpart of [`MultiOps::intersection`], it stops consuming
+    /// the operands as soon as `self` is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb: RoaringBitmap = (1..10).collect();
+    /// rb.intersect_all([(3..20).collect(), (0..5).collect()]);
+    ///
+    /// assert_eq!(rb, RoaringBitmap::from([3, 4]));
+    /// ```
+    pub fn intersect_all<I: IntoIterator<Item = RoaringBitmap>>(&mut self, others: I) {
+        for bitmap in others {
+            if self.is_empty() {
+                return;
+            }
+            *self &= bitmap;
+        }
+    }
+
+    /// Removes in-place from `self` all the values of the given bitmaps.
+    ///
+    /// This is the in-place counterpart of [`MultiOps::difference`], it stops consuming
+    /// the operands as soon as `self` is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb: RoaringBitmap = (1..10).collect();
+    /// rb.difference_all([(3..5).collect(), (8..20).collect()]);
+    ///
+    /// assert_eq!(rb, RoaringBitmap::from([1, 2, 5, 6, 7]));
+    /// ```
+    pub fn difference_all<I: IntoIterator<Item = RoaringBitmap>>(&mut self, others: I) {
+        for bitmap in others {
+            if self.is_empty() {
+                return;
+            }
+            *self -= bitmap;
+        }
+    }
+}
+
 #[inline]
 fn try_multi_and_owned<E>(
     bitmaps: impl IntoIterator<Item = Result<RoaringBitmap, E>>,
@@ -332,7 +417,7 @@ fn try_multi_or_ref<'a, E: 'a>(
     // Phase 3: Clean up
     let containers: Vec<_> = containers
         .into_iter()
-        .filter(|container| container.len() > 0)
+        .filter(|container| !container.is_empty())
         .map(|c| {
             // Any borrowed bitmaps or arrays left over get cloned here
             let mut container = c.into_owned();
@@ -373,7 +458,7 @@ fn try_multi_xor_ref<'a, E: 'a>(
     // Phase 3: Clean up
     let containers: Vec<_> = containers
         .into_iter()
-        .filter(|container| container.len() > 0)
+        .filter(|container| !container.is_empty())
         .map(|c| {
             // Any borrowed bitmaps or arrays left over get cloned here
             let mut container = c.into_owned();
@@ -1,8 +1,13 @@
+use core::iter;
 use core::mem;
-use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Sub, SubAssign};
+use core::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Bound, RangeInclusive, Sub,
+    SubAssign,
+};
 
 use crate::bitmap::container::Container;
-use crate::bitmap::Pairs;
+use crate::bitmap::store::Store;
+use crate::bitmap::{util, Pairs};
 use crate::RoaringBitmap;
 
 #[cfg(not(feature = "std"))]
@@ -27,14 +32,95 @@ impl RoaringBitmap {
     /// assert_eq!(rb1.intersection_len(&rb2), (rb1 & rb2).len());
     /// ```
     pub fn intersection_len(&self, other: &RoaringBitmap) -> u64 {
-        Pairs::new(&self.containers, &other.containers)
-            .map(|pair| match pair {
-                (Some(..), None) => 0,
-                (None, Some(..)) => 0,
-                (Some(lhs), Some(rhs)) => lhs.intersection_len(rhs),
-                (None, None) => 0,
-            })
-            .sum()
+        if self.is_empty() || other.is_empty() {
+            return 0;
+        }
+        // a bitmap is a subset of itself
+        if core::ptr::eq(self, other) {
+            return self.len();
+        }
+
+        let (lhs, rhs) = overlapping_containers(&self.containers, &other.containers);
+        intersection_len(lhs, rhs)
+    }
+
+    /// Returns `true` if the intersection with the specified other bitmap has at least
+    /// `threshold` values, without creating a new bitmap.
+    ///
+    /// The len of the intersection is accumulated container by container and the computation
+    /// stops as soon as it reaches the threshold. It doesn't start at all when one of the
+    /// bitmaps is smaller than the threshold.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb1: RoaringBitmap = (1..100).collect();
+    /// let rb2: RoaringBitmap = (90..200).collect();
+    ///
+    /// assert!(rb1.intersection_len_at_least(&rb2, 10));
+    /// assert!(!rb1.intersection_len_at_least(&rb2, 11));
+    /// assert!(rb1.intersection_len_at_least(&RoaringBitmap::new(), 0));
+    /// ```
+    pub fn intersection_len_at_least(&self, other: &RoaringBitmap, threshold: u64) -> bool {
+        if threshold == 0 {
+            return true;
+        }
+        if self.len().min(other.len()) < threshold {
+            return false;
+        }
+
+        let (lhs, rhs) = overlapping_containers(&self.containers, &other.containers);
+        let mut len = 0;
+        for pair in Pairs::new(lhs, rhs) {
+            if let (Some(lhs), Some(rhs)) = pair {
+                len += lhs.intersection_len(rhs);
+                if len >= threshold {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Computes the intersection of this bitmap with all the specified other bitmaps.
+    ///
+    /// The operands are processed from the smallest to the largest, so that the containers
+    /// missing from any of them are pruned as early as possible. The computation stops as soon
+    /// as the intersection becomes empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb1: RoaringBitmap = (1..10).collect();
+    /// let rb2: RoaringBitmap = (3..20).collect();
+    /// let rb3: RoaringBitmap = (0..5).collect();
+    ///
+    /// assert_eq!(rb1.intersection_with_all(&[&rb2, &rb3]), RoaringBitmap::from([3, 4]));
+    /// assert_eq!(rb1.intersection_with_all(&[]), rb1);
+    /// ```
+    pub fn intersection_with_all(&self, others: &[&RoaringBitmap]) -> RoaringBitmap {
+        let mut operands: Vec<(u64, &RoaringBitmap)> =
+            others.iter().map(|bitmap| (bitmap.len(), *bitmap)).collect();
+        operands.sort_unstable_by_key(|(len, _)| *len);
+
+        let mut operands = operands.into_iter().map(|(_, bitmap)| bitmap);
+        let mut result = match operands.next() {
+            Some(smallest) => BitAnd::bitand(self, smallest),
+            None => return self.clone(),
+        };
+
+        for bitmap in operands {
+            if result.is_empty() {
+                break;
+            }
+            BitAndAssign::bitand_assign(&mut result, bitmap);
+        }
+
+        result
     }
 
     /// Computes the len of the union with the specified other bitmap without creating a new bitmap.
@@ -57,6 +143,38 @@ impl RoaringBitmap {
         self.len().wrapping_add(other.len()).wrapping_sub(self.intersection_len(other))
     }
 
+    /// Computes an upper bound of the len of the union with the specified other bitmap, in time
+    /// proportional to the number of containers rather than the number of values.
+    ///
+    /// The bound is exact when the bitmaps are disjoint, or when the containers they share are
+    /// dense enough for their values to necessarily overlap. Use [`RoaringBitmap::union_len`] to
+    /// get the exact len.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    /// let rb3: RoaringBitmap = (100_000..100_010).collect();
+    ///
+    /// assert!(rb1.union_len_upper_bound(&rb2) >= rb1.union_len(&rb2));
+    /// assert_eq!(rb1.union_len_upper_bound(&rb3), rb1.union_len(&rb3));
+    /// ```
+    pub fn union_len_upper_bound(&self, other: &RoaringBitmap) -> u64 {
+        // Two containers sharing a key must have at least this many values in common.
+        let (lhs, rhs) = overlapping_containers(&self.containers, &other.containers);
+        let intersection_lower_bound: u64 = Pairs::new(lhs, rhs)
+            .map(|pair| match pair {
+                (Some(lhs), Some(rhs)) => (lhs.len() + rhs.len()).saturating_sub(1 << 16),
+                _ => 0,
+            })
+            .sum();
+
+        self.len() + other.len() - intersection_lower_bound
+    }
+
     /// Computes the len of the difference with the specified other bitmap without creating a new
     /// bitmap.
     ///
@@ -75,7 +193,34 @@ impl RoaringBitmap {
     /// assert_eq!(rb1.difference_len(&rb2), (rb1 - rb2).len());
     /// ```
     pub fn difference_len(&self, other: &RoaringBitmap) -> u64 {
-        self.len() - self.intersection_len(other)
+        if core::ptr::eq(self, other) {
+            return 0;
+        }
+        // The overlapping containers are empty when the bitmaps are disjoint,
+        // nothing is removed from `self` then.
+        let (lhs, rhs) = overlapping_containers(&self.containers, &other.containers);
+        self.len() - intersection_len(lhs, rhs)
+    }
+
+    /// Computes the len of the difference with the specified other bitmap without creating a new
+    /// bitmap.
+    ///
+    /// This is [`RoaringBitmap::difference_len`] under the name of CRoaring's
+    /// `andnot_cardinality`. Disjoint bitmaps and a bitmap compared with itself
+    /// are answered without visiting the values.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    ///
+    /// assert_eq!(rb1.and_not_len(&rb2), (rb1 - rb2).len());
+    /// ```
+    pub fn and_not_len(&self, other: &RoaringBitmap) -> u64 {
+        self.difference_len(other)
     }
 
     /// Computes the len of the symmetric difference with the specified other bitmap without
@@ -102,6 +247,484 @@ impl RoaringBitmap {
             .wrapping_sub(intersection_len)
             .wrapping_sub(intersection_len)
     }
+
+    /// Retains only the values that are also in the specified other bitmap.
+    ///
+    /// This is the in-place intersection performed by `self &= other`, except that `self` is
+    /// never cloned: the vector of containers is only shrunk and the array containers are
+    /// filtered in place. A bitset container may still be replaced by a new array container
+    /// when the result is small. This is the method to use when intersecting a large bitmap
+    /// with a small one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    ///
+    /// rb1.intersect_with(&rb2);
+    /// assert_eq!(rb1, RoaringBitmap::from([3]));
+    /// ```
+    pub fn intersect_with(&mut self, other: &RoaringBitmap) {
+        self.containers.retain_mut(|cont| {
+            match other.containers.binary_search_by_key(&cont.key, |c| c.key) {
+                Ok(loc) => {
+                    cont.store.intersect_with(&other.containers[loc].store);
+                    cont.ensure_correct_store();
+                    !cont.is_empty()
+                }
+                Err(_) => false,
+            }
+        })
+    }
+
+    /// Adds the values of the specified other bitmap, this is an alias for `self |= other`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    ///
+    /// rb1.union_with(&rb2);
+    /// assert_eq!(rb1, RoaringBitmap::from([1, 2, 3, 4]));
+    /// ```
+    pub fn union_with(&mut self, other: &RoaringBitmap) {
+        BitOrAssign::bitor_assign(self, other)
+    }
+
+    /// Removes the values of the specified other bitmap, this is an alias for `self -= other`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    ///
+    /// rb1.difference_with(&rb2);
+    /// assert_eq!(rb1, RoaringBitmap::from([1, 2]));
+    /// ```
+    pub fn difference_with(&mut self, other: &RoaringBitmap) {
+        SubAssign::sub_assign(self, other)
+    }
+
+    /// Keeps the values that are in exactly one of the two bitmaps, this is an alias for
+    /// `self ^= other`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    ///
+    /// rb1.symmetric_difference_with(&rb2);
+    /// assert_eq!(rb1, RoaringBitmap::from([1, 2, 4]));
+    /// ```
+    pub fn symmetric_difference_with(&mut self, other: &RoaringBitmap) {
+        BitXorAssign::bitxor_assign(self, other)
+    }
+
+    /// Retains only the values that are also yielded by `sorted`, in a single pass.
+    ///
+    /// This is equivalent to intersecting with a bitmap built from `sorted`, without building it.
+    /// Each container is filtered in place by the values sharing its key, array containers are
+    /// retained with a cursor and the bits missing from `sorted` are cleared in bitmap containers.
+    ///
+    /// The values of `sorted` must be in ascending order, duplicates are allowed. Values out of
+    /// order are ignored and lead to a wrong result, this is checked by a debug assertion.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb: RoaringBitmap = (1..4).collect();
+    /// rb.intersect_with_sorted([0, 2, 3, 3, 100_000]);
+    /// assert_eq!(rb, RoaringBitmap::from([2, 3]));
+    /// ```
+    pub fn intersect_with_sorted<I: IntoIterator<Item = u32>>(&mut self, sorted: I) {
+        let mut previous = None;
+        let mut sorted = sorted
+            .into_iter()
+            .inspect(|&value| {
+                debug_assert!(previous <= Some(value), "values must be sorted");
+                previous = Some(value);
+            })
+            .peekable();
+
+        self.containers.retain_mut(|container| {
+            let key = u32::from(container.key);
+            while sorted.next_if(|&value| value >> 16 < key).is_some() {}
+            if sorted.peek().is_none() {
+                return false;
+            }
+            let values = iter::from_fn(|| sorted.next_if(|&value| value >> 16 == key));
+            let values = values.map(|value| value as u16);
+
+            match &mut container.store {
+                Store::Array(array) => {
+                    let mut values = values.peekable();
+                    array.retain(|low| {
+                        while values.next_if(|&value| value < low).is_some() {}
+                        values.peek() == Some(&low)
+                    });
+                }
+                Store::Bitmap(bits) => bits.retain_sorted(values),
+            }
+            container.ensure_correct_store();
+            !container.is_empty()
+        });
+    }
+
+    /// Retains only the values that are also in the `sorted` slice.
+    ///
+    /// This is equivalent to intersecting with a bitmap built from `sorted`, without building it.
+    /// The slice is walked once, each container is filtered in place by the values of the slice
+    /// sharing its key: array containers are retained with a cursor moving through these values
+    /// and bitset containers only keep the bits of these values.
+    ///
+    /// The values of `sorted` must be in ascending order, duplicates are allowed. Values out of
+    /// order lead to a wrong result, this is checked by a debug assertion.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb: RoaringBitmap = (1..4).chain(100_000..200_000).collect();
+    /// rb.intersect_with_sorted_slice(&[0, 2, 3, 3, 100_000, 150_000, u32::MAX]);
+    /// assert_eq!(rb, RoaringBitmap::from([2, 3, 100_000, 150_000]));
+    /// ```
+    pub fn intersect_with_sorted_slice(&mut self, sorted: &[u32]) {
+        debug_assert!(sorted.windows(2).all(|pair| pair[0] <= pair[1]), "values must be sorted");
+
+        let mut rest = sorted;
+        self.containers.retain_mut(|container| {
+            let key = u32::from(container.key);
+            rest = &rest[rest.partition_point(|&value| value >> 16 < key)..];
+            let (values, tail) = rest.split_at(rest.partition_point(|&value| value >> 16 == key));
+            rest = tail;
+
+            let values = values.iter().map(|&value| value as u16);
+            match &mut container.store {
+                Store::Array(array) => {
+                    let mut values = values.peekable();
+                    array.retain(|low| {
+                        while values.next_if(|&value| value < low).is_some() {}
+                        values.peek() == Some(&low)
+                    });
+                }
+                Store::Bitmap(bits) => bits.retain_sorted(values),
+            }
+            container.ensure_correct_store();
+            !container.is_empty()
+        });
+    }
+
+    /// Removes the values that are yielded by `sorted`, in a single pass.
+    ///
+    /// This is equivalent to subtracting a bitmap built from `sorted`, without building it.
+    ///
+    /// The values of `sorted` must be in ascending order, duplicates are allowed. Values out of
+    /// order are ignored and lead to a wrong result, this is checked by a debug assertion.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb: RoaringBitmap = (1..4).collect();
+    /// rb.difference_with_sorted([0, 2, 3, 3, 100_000]);
+    /// assert_eq!(rb, RoaringBitmap::from([1]));
+    /// ```
+    pub fn difference_with_sorted<I: IntoIterator<Item = u32>>(&mut self, sorted: I) {
+        let mut index = 0;
+        let mut previous = None;
+        for value in sorted {
+            debug_assert!(previous <= Some(value), "values must be sorted");
+            previous = Some(value);
+
+            let (key, low) = util::split(value);
+            if seek_container(&self.containers, &mut index, key).is_some() {
+                self.containers[index].remove(low);
+            } else if index == self.containers.len() {
+                break;
+            }
+        }
+        self.containers.retain(|container| !container.is_empty());
+    }
+
+    /// Writes the intersection with the specified other bitmap into `out`.
+    ///
+    /// The previous content of `out` is overwritten, but its containers and their allocations
+    /// are reused. This avoids allocating a new bitmap when an operation is repeated in a loop.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    /// let mut out = RoaringBitmap::new();
+    ///
+    /// rb1.intersection_into(&rb2, &mut out);
+    /// assert_eq!(out, &rb1 & &rb2);
+    /// ```
+    pub fn intersection_into(&self, other: &RoaringBitmap, out: &mut RoaringBitmap) {
+        let mut len = 0;
+        for pair in Pairs::new(&self.containers, &other.containers) {
+            if let (Some(lhs), Some(rhs)) = pair {
+                let container = reuse_container(&mut out.containers, len, lhs);
+                BitAndAssign::bitand_assign(container, rhs);
+                if !container.is_empty() {
+                    len += 1;
+                }
+            }
+        }
+        out.containers.truncate(len);
+    }
+
+    /// Writes the union with the specified other bitmap into `out`.
+    ///
+    /// The previous content of `out` is overwritten, but its containers and their allocations
+    /// are reused. This avoids allocating a new bitmap when an operation is repeated in a loop.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    /// let mut out = RoaringBitmap::new();
+    ///
+    /// rb1.union_into(&rb2, &mut out);
+    /// assert_eq!(out, &rb1 | &rb2);
+    /// ```
+    pub fn union_into(&self, other: &RoaringBitmap, out: &mut RoaringBitmap) {
+        let mut len = 0;
+        for pair in Pairs::new(&self.containers, &other.containers) {
+            match pair {
+                (Some(lhs), Some(rhs)) => {
+                    let container = reuse_container(&mut out.containers, len, lhs);
+                    BitOrAssign::bitor_assign(container, rhs);
+                }
+                (Some(source), None) | (None, Some(source)) => {
+                    reuse_container(&mut out.containers, len, source);
+                }
+                (None, None) => break,
+            }
+            len += 1;
+        }
+        out.containers.truncate(len);
+    }
+
+    /// Writes the difference with the specified other bitmap into `out`.
+    ///
+    /// The previous content of `out` is overwritten, but its containers and their allocations
+    /// are reused. This avoids allocating a new bitmap when an operation is repeated in a loop.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    /// let mut out = RoaringBitmap::new();
+    ///
+    /// rb1.difference_into(&rb2, &mut out);
+    /// assert_eq!(out, &rb1 - &rb2);
+    /// ```
+    pub fn difference_into(&self, other: &RoaringBitmap, out: &mut RoaringBitmap) {
+        let mut len = 0;
+        for pair in Pairs::new(&self.containers, &other.containers) {
+            match pair {
+                (Some(lhs), Some(rhs)) => {
+                    let container = reuse_container(&mut out.containers, len, lhs);
+                    SubAssign::sub_assign(container, rhs);
+                    if !container.is_empty() {
+                        len += 1;
+                    }
+                }
+                (Some(lhs), None) => {
+                    reuse_container(&mut out.containers, len, lhs);
+                    len += 1;
+                }
+                (None, _) => (),
+            }
+        }
+        out.containers.truncate(len);
+    }
+
+    /// Writes the symmetric difference with the specified other bitmap into `out`.
+    ///
+    /// The previous content of `out` is overwritten, but its containers and their allocations
+    /// are reused. This avoids allocating a new bitmap when an operation is repeated in a loop.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    /// let mut out = RoaringBitmap::new();
+    ///
+    /// rb1.symmetric_difference_into(&rb2, &mut out);
+    /// assert_eq!(out, &rb1 ^ &rb2);
+    /// ```
+    pub fn symmetric_difference_into(&self, other: &RoaringBitmap, out: &mut RoaringBitmap) {
+        let mut len = 0;
+        for pair in Pairs::new(&self.containers, &other.containers) {
+            match pair {
+                (Some(lhs), Some(rhs)) => {
+                    let container = reuse_container(&mut out.containers, len, lhs);
+                    BitXorAssign::bitxor_assign(container, rhs);
+                    if !container.is_empty() {
+                        len += 1;
+                    }
+                }
+                (Some(source), None) | (None, Some(source)) => {
+                    reuse_container(&mut out.containers, len, source);
+                    len += 1;
+                }
+                (None, None) => break,
+            }
+        }
+        out.containers.truncate(len);
+    }
+
+    /// Estimates the Jaccard index, the ratio between the intersection len and the union len,
+    /// with the specified other bitmap without computing the intersection.
+    ///
+    /// Only the len and the bounds of the containers are used: containers with non-overlapping
+    /// bounds do not intersect, and the values of the others are assumed to be evenly and
+    /// independently spread between their min and max. This makes it a cheap pre-filter before
+    /// computing the exact index with [`RoaringBitmap::intersection_len`] and
+    /// [`RoaringBitmap::union_len`].
+    ///
+    /// With random values the estimate is usually within 0.01 of the exact index, but
+    /// correlated bitmaps are underestimated, e.g. a sparse bitmap compared with itself.
+    /// Two empty bitmaps are equal and have an index of 1.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb1: RoaringBitmap = (0..200_000).collect();
+    /// let rb2: RoaringBitmap = (100_000..300_000).collect();
+    /// let rb3: RoaringBitmap = (400_000..500_000).collect();
+    ///
+    /// let exact = rb1.intersection_len(&rb2) as f64 / rb1.union_len(&rb2) as f64;
+    /// assert!((rb1.estimated_jaccard(&rb2) - exact).abs() < 0.01);
+    /// assert_eq!(rb1.estimated_jaccard(&rb3), 0.0);
+    /// ```
+    pub fn estimated_jaccard(&self, other: &RoaringBitmap) -> f64 {
+        let (lhs, rhs) = overlapping_containers(&self.containers, &other.containers);
+        let intersection_len: f64 = Pairs::new(lhs, rhs)
+            .map(|pair| match pair {
+                (Some(lhs), Some(rhs)) => estimated_intersection_len(lhs, rhs),
+                _ => 0.0,
+            })
+            .sum();
+
+        let union_len = self.len() as f64 + other.len() as f64 - intersection_len;
+        if union_len == 0.0 {
+            1.0
+        } else {
+            intersection_len / union_len
+        }
+    }
+}
+
+/// Returns the containers of each side whose keys lie between the first and
+/// last keys of the other side, the only ones that can share values.
+fn overlapping_containers<'a>(
+    lhs: &'a [Container],
+    rhs: &'a [Container],
+) -> (&'a [Container], &'a [Container]) {
+    fn within<'a>(containers: &'a [Container], bounds: &[Container]) -> &'a [Container] {
+        match (bounds.first(), bounds.last()) {
+            (Some(first), Some(last)) => {
+                let start = containers.partition_point(|c| c.key < first.key);
+                let end = containers.partition_point(|c| c.key <= last.key);
+                &containers[start..end]
+            }
+            _ => &[],
+        }
+    }
+
+    (within(lhs, rhs), within(rhs, lhs))
+}
+
+/// Computes the len of the intersection of two sorted slices of containers.
+fn intersection_len(lhs: &[Container], rhs: &[Container]) -> u64 {
+    Pairs::new(lhs, rhs)
+        .map(|pair| match pair {
+            (Some(lhs), Some(rhs)) => lhs.intersection_len(rhs),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Searches the container with `key` from `index`, the containers before it having smaller
+/// keys, and moves `index` to this container or to the first one with a greater key.
+fn seek_container<'a>(
+    containers: &'a [Container],
+    index: &mut usize,
+    key: u16,
+) -> Option<&'a Container> {
+    match containers[*index..].binary_search_by_key(&key, |container| container.key) {
+        Ok(offset) => {
+            *index += offset;
+            Some(&containers[*index])
+        }
+        Err(offset) => {
+            *index += offset;
+            None
+        }
+    }
+}
+
+/// Overwrites the container at `index` with `source`, reusing its allocation, or pushes
+/// a clone of `source` when there is no container at `index` yet.
+fn reuse_container<'a>(
+    containers: &'a mut Vec<Container>,
+    index: usize,
+    source: &Container,
+) -> &'a mut Container {
+    if index < containers.len() {
+        containers[index].clone_from(source);
+    } else {
+        containers.push(source.clone());
+    }
+    &mut containers[index]
+}
+
+/// Estimates the intersection len of two containers from their len and bounds.
+fn estimated_intersection_len(lhs: &Container, rhs: &Container) -> f64 {
+    let (lhs_min, lhs_max) = (lhs.min().unwrap(), lhs.max().unwrap());
+    let (rhs_min, rhs_max) = (rhs.min().unwrap(), rhs.max().unwrap());
+    let (start, end) = (lhs_min.max(rhs_min), lhs_max.min(rhs_max));
+    if start > end {
+        return 0.0;
+    }
+
+    let overlap = f64::from(end - start) + 1.0;
+    let lhs_density = lhs.len() as f64 / (f64::from(lhs_max - lhs_min) + 1.0);
+    let rhs_density = rhs.len() as f64 / (f64::from(rhs_max - rhs_min) + 1.0);
+    (overlap * lhs_density * rhs_density).min(lhs.len().min(rhs.len()) as f64)
 }
 
 impl BitOr<RoaringBitmap> for RoaringBitmap {
@@ -137,7 +760,16 @@ impl BitOr<&RoaringBitmap> for &RoaringBitmap {
     type Output = RoaringBitmap;
 
     /// An `union` between two sets.
+    ///
+    /// The union of a bitmap with itself is a copy of it, computed without walking the
+    /// containers.
     fn bitor(self, rhs: &RoaringBitmap) -> RoaringBitmap {
+        if rhs.is_empty() || core::ptr::eq(self, rhs) {
+            return self.clone();
+        } else if self.is_empty() {
+            return rhs.clone();
+        }
+
         let mut containers = Vec::new();
 
         for pair in Pairs::new(&self.containers, &rhs.containers) {
@@ -174,6 +806,11 @@ impl BitOrAssign<RoaringBitmap> for RoaringBitmap {
 impl BitOrAssign<&RoaringBitmap> for RoaringBitmap {
     /// An `union` between two sets.
     fn bitor_assign(&mut self, rhs: &RoaringBitmap) {
+        if self.is_empty() {
+            self.clone_from(rhs);
+            return;
+        }
+
         for container in &rhs.containers {
             let key = container.key;
             match self.containers.binary_search_by_key(&key, |c| c.key) {
@@ -217,7 +854,16 @@ impl BitAnd<&RoaringBitmap> for &RoaringBitmap {
     type Output = RoaringBitmap;
 
     /// An `intersection` between two sets.
+    ///
+    /// The intersection of a bitmap with itself is a copy of it, computed without walking the
+    /// containers.
     fn bitand(self, rhs: &RoaringBitmap) -> RoaringBitmap {
+        if self.is_empty() || rhs.is_empty() {
+            return RoaringBitmap::new();
+        } else if core::ptr::eq(self, rhs) {
+            return self.clone();
+        }
+
         let mut containers = Vec::new();
 
         for pair in Pairs::new(&self.containers, &rhs.containers) {
@@ -235,6 +881,9 @@ impl BitAnd<&RoaringBitmap> for &RoaringBitmap {
 
 impl BitAndAssign<RoaringBitmap> for RoaringBitmap {
     /// An `intersection` between two sets.
+    ///
+    /// The containers of the operand with the fewest containers are reused and
+    /// intersected in place, nothing is cloned.
     fn bitand_assign(&mut self, mut rhs: RoaringBitmap) {
         // We make sure that we apply the intersection operation on the smallest map.
         if rhs.containers.len() < self.containers.len() {
@@ -259,6 +908,11 @@ impl BitAndAssign<RoaringBitmap> for RoaringBitmap {
 impl BitAndAssign<&RoaringBitmap> for RoaringBitmap {
     /// An `intersection` between two sets.
     fn bitand_assign(&mut self, rhs: &RoaringBitmap) {
+        if rhs.is_empty() {
+            self.clear();
+            return;
+        }
+
         self.containers.retain_mut(|cont| {
             let key = cont.key;
             match rhs.containers.binary_search_by_key(&key, |c| c.key) {
@@ -272,6 +926,62 @@ impl BitAndAssign<&RoaringBitmap> for RoaringBitmap {
     }
 }
 
+impl BitAnd<RangeInclusive<u32>> for RoaringBitmap {
+    type Output = RoaringBitmap;
+
+    /// Restricts the set to the values within the range.
+    fn bitand(mut self, rhs: RangeInclusive<u32>) -> RoaringBitmap {
+        BitAndAssign::bitand_assign(&mut self, rhs);
+        self
+    }
+}
+
+impl BitAnd<RangeInclusive<u32>> for &RoaringBitmap {
+    type Output = RoaringBitmap;
+
+    /// Restricts the set to the values within the range.
+    ///
+    /// Only the containers overlapping the range are cloned.
+    fn bitand(self, rhs: RangeInclusive<u32>) -> RoaringBitmap {
+        if rhs.is_empty() {
+            return RoaringBitmap::new();
+        }
+
+        let (start_key, _) = util::split(*rhs.start());
+        let (end_key, _) = util::split(*rhs.end());
+        let start = self.containers.partition_point(|c| c.key < start_key);
+        let end = self.containers.partition_point(|c| c.key <= end_key);
+
+        let mut bitmap = RoaringBitmap { containers: self.containers[start..end].to_vec() };
+        BitAndAssign::bitand_assign(&mut bitmap, rhs);
+        bitmap
+    }
+}
+
+impl BitAndAssign<RangeInclusive<u32>> for RoaringBitmap {
+    /// Restricts the set to the values within the range, in place.
+    ///
+    /// The containers outside of the range are dropped and only the
+    /// two containers at the edges of the range are modified.
+    fn bitand_assign(&mut self, rhs: RangeInclusive<u32>) {
+        if rhs.is_empty() {
+            self.clear();
+            return;
+        }
+
+        let (start, end) = (*rhs.start(), *rhs.end());
+        let (start_key, _) = util::split(start);
+        let (end_key, _) = util::split(end);
+        let end_index = self.containers.partition_point(|c| c.key <= end_key);
+        self.containers.truncate(end_index);
+        let start_index = self.containers.partition_point(|c| c.key < start_key);
+        self.containers.drain(..start_index);
+
+        self.remove_range(..start);
+        self.remove_range((Bound::Excluded(end), Bound::Unbounded));
+    }
+}
+
 impl Sub<RoaringBitmap> for RoaringBitmap {
     type Output = RoaringBitmap;
 
@@ -286,6 +996,8 @@ impl Sub<&RoaringBitmap> for RoaringBitmap {
     type Output = RoaringBitmap;
 
     /// A `difference` between two sets.
+    ///
+    /// The difference is computed in place, reusing the containers of `self`.
     fn sub(mut self, rhs: &RoaringBitmap) -> RoaringBitmap {
         SubAssign::sub_assign(&mut self, rhs);
         self
@@ -305,7 +1017,16 @@ impl Sub<&RoaringBitmap> for &RoaringBitmap {
     type Output = RoaringBitmap;
 
     /// A `difference` between two sets.
+    ///
+    /// The difference of a bitmap with itself is empty, it is returned without walking the
+    /// containers.
     fn sub(self, rhs: &RoaringBitmap) -> RoaringBitmap {
+        if self.is_empty() || core::ptr::eq(self, rhs) {
+            return RoaringBitmap::new();
+        } else if rhs.is_empty() {
+            return self.clone();
+        }
+
         let mut containers = Vec::new();
 
         for pair in Pairs::new(&self.containers, &rhs.containers) {
@@ -335,7 +1056,14 @@ impl SubAssign<RoaringBitmap> for RoaringBitmap {
 
 impl SubAssign<&RoaringBitmap> for RoaringBitmap {
     /// A `difference` between two sets.
+    ///
+    /// A difference never adds values: the containers are updated in place, shrunk to arrays
+    /// when they become small enough and dropped when they become empty.
     fn sub_assign(&mut self, rhs: &RoaringBitmap) {
+        if self.is_empty() || rhs.is_empty() {
+            return;
+        }
+
         self.containers.retain_mut(|cont| {
             match rhs.containers.binary_search_by_key(&cont.key, |c| c.key) {
                 Ok(loc) => {
@@ -381,7 +1109,18 @@ impl BitXor<&RoaringBitmap> for &RoaringBitmap {
     type Output = RoaringBitmap;
 
     /// A `symmetric difference` between two sets.
+    ///
+    /// The symmetric difference of a bitmap with itself is empty, it is returned without
+    /// walking the containers.
     fn bitxor(self, rhs: &RoaringBitmap) -> RoaringBitmap {
+        if core::ptr::eq(self, rhs) {
+            return RoaringBitmap::new();
+        } else if rhs.is_empty() {
+            return self.clone();
+        } else if self.is_empty() {
+            return rhs.clone();
+        }
+
         let mut containers = Vec::new();
 
         for pair in Pairs::new(&self.containers, &rhs.containers) {
@@ -404,19 +1143,47 @@ impl BitXor<&RoaringBitmap> for &RoaringBitmap {
 
 impl BitXorAssign<RoaringBitmap> for RoaringBitmap {
     /// A `symmetric difference` between two sets.
-    fn bitxor_assign(&mut self, rhs: RoaringBitmap) {
-        for pair in Pairs::new(mem::take(&mut self.containers), rhs.containers) {
-            match pair {
-                (Some(mut lhs), Some(rhs)) => {
-                    BitXorAssign::bitxor_assign(&mut lhs, rhs);
-                    if !lhs.is_empty() {
-                        self.containers.push(lhs);
-                    }
-                }
-                (Some(lhs), None) => self.containers.push(lhs),
-                (None, Some(rhs)) => self.containers.push(rhs),
-                (None, None) => break,
+    ///
+    /// The containers of the operand with the most containers are reused and
+    /// the other ones are merged into them, nothing is cloned.
+    fn bitxor_assign(&mut self, mut rhs: RoaringBitmap) {
+        // We make sure that we apply the symmetric difference operation on the biggest map.
+        if self.containers.len() < rhs.containers.len() {
+            mem::swap(self, &mut rhs);
+        }
+
+        let lhs = &mut self.containers;
+        let mut rhs = rhs.containers;
+
+        // Apply the operation on the containers with a matching key, only keeping
+        // in `rhs` the ones that must be inserted in `lhs`.
+        let mut emptied = false;
+        rhs.retain_mut(|cont| match lhs.binary_search_by_key(&cont.key, |c| c.key) {
+            Ok(loc) => {
+                let cont = mem::replace(cont, Container::new(cont.key));
+                BitXorAssign::bitxor_assign(&mut lhs[loc], cont);
+                emptied |= lhs[loc].is_empty();
+                false
+            }
+            Err(_) => true,
+        });
+
+        // Merge the remaining, disjoint, containers from the back so that
+        // every container is moved at most once.
+        let (mut i, mut k) = (lhs.len(), lhs.len() + rhs.len());
+        lhs.resize_with(k, || Container::new(0));
+        while let Some(last) = rhs.last() {
+            if i > 0 && lhs[i - 1].key > last.key {
+                lhs.swap(i - 1, k - 1);
+                i -= 1;
+            } else {
+                lhs[k - 1] = rhs.pop().unwrap();
             }
+            k -= 1;
+        }
+
+        if emptied {
+            lhs.retain(|cont| !cont.is_empty());
         }
     }
 }
@@ -424,6 +1191,13 @@ impl BitXorAssign<RoaringBitmap> for RoaringBitmap {
 impl BitXorAssign<&RoaringBitmap> for RoaringBitmap {
     /// A `symmetric difference` between two sets.
     fn bitxor_assign(&mut self, rhs: &RoaringBitmap) {
+        if rhs.is_empty() {
+            return;
+        } else if self.is_empty() {
+            self.clone_from(rhs);
+            return;
+        }
+
         for pair in Pairs::new(mem::take(&mut self.containers), &rhs.containers) {
             match pair {
                 (Some(mut lhs), Some(rhs)) => {
@@ -446,6 +1220,9 @@ mod test {
     use core::convert::Infallible;
     use proptest::prelude::*;
 
+    #[cfg(not(feature = "std"))]
+    use alloc::vec::Vec;
+
     // fast count tests
     proptest! {
         #[test]
@@ -472,6 +1249,74 @@ mod test {
             prop_assert_eq!(a.difference_len(&b), (a - b).len());
         }
 
+        #[test]
+        fn len_of_shifted_operands(
+            a in RoaringBitmap::arbitrary(),
+            b in RoaringBitmap::arbitrary(),
+            key_shift in 0u32..32,
+        ) {
+            // Moves `b` around `a` so that their keys are partially or not at all overlapping
+            let mut shifted = RoaringBitmap::new();
+            shifted.insert_shifted(&b, key_shift << 16);
+            prop_assert_eq!(a.intersection_len(&shifted), (&a & &shifted).len());
+            prop_assert_eq!(a.difference_len(&shifted), (&a - &shifted).len());
+            prop_assert_eq!(shifted.difference_len(&a), (&shifted - &a).len());
+        }
+
+        #[test]
+        fn all_into_give_the_same_result(
+            a in RoaringBitmap::arbitrary(),
+            b in RoaringBitmap::arbitrary(),
+            previous in RoaringBitmap::arbitrary()
+        ) {
+            let mut out = previous.clone();
+            a.intersection_into(&b, &mut out);
+            prop_assert_eq!(&out, &(&a & &b));
+
+            let mut out = previous.clone();
+            a.union_into(&b, &mut out);
+            prop_assert_eq!(&out, &(&a | &b));
+
+            let mut out = previous.clone();
+            a.difference_into(&b, &mut out);
+            prop_assert_eq!(&out, &(&a - &b));
+
+            let mut out = previous;
+            a.symmetric_difference_into(&b, &mut out);
+            prop_assert_eq!(&out, &(&a ^ &b));
+        }
+
+        #[test]
+        fn with_sorted_give_the_same_result(
+            a in RoaringBitmap::arbitrary(),
+            b in RoaringBitmap::arbitrary()
+        ) {
+            let sorted = b.iter().flat_map(|value| [value, value]);
+
+            let mut intersection = a.clone();
+            intersection.intersect_with_sorted(sorted.clone());
+            prop_assert_eq!(&intersection, &(&a & &b));
+            prop_assert_eq!(intersection.validate(), Ok(()));
+
+            let mut intersection = a.clone();
+            intersection.intersect_with_sorted_slice(&sorted.clone().collect::<Vec<u32>>());
+            prop_assert_eq!(&intersection, &(&a & &b));
+            prop_assert_eq!(intersection.validate(), Ok(()));
+
+            let mut difference = a.clone();
+            difference.difference_with_sorted(sorted);
+            prop_assert_eq!(&difference, &(&a - &b));
+        }
+
+        #[test]
+        fn estimated_jaccard_is_bounded(
+            a in RoaringBitmap::arbitrary(),
+            b in RoaringBitmap::arbitrary()
+        ) {
+            let estimate = a.estimated_jaccard(&b);
+            prop_assert!((0.0..=1.0).contains(&estimate));
+        }
+
         #[test]
         fn symmetric_difference_len_eq_len_of_materialized_symmetric_difference(
             a in RoaringBitmap::arbitrary(),
@@ -630,4 +1475,56 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn estimated_jaccard() {
+        let empty = RoaringBitmap::new();
+        assert_eq!(empty.estimated_jaccard(&empty), 1.0);
+
+        let dense: RoaringBitmap = (0..300_000).collect();
+        assert_eq!(dense.estimated_jaccard(&empty), 0.0);
+        assert_eq!(dense.estimated_jaccard(&dense), 1.0);
+
+        // The bounds of the containers do not overlap
+        let even: RoaringBitmap = (0..100).map(|i| i * 2).collect();
+        let above: RoaringBitmap = (200..300).collect();
+        assert_eq!(even.estimated_jaccard(&above), 0.0);
+    }
+
+    #[test]
+    fn owned_intersection_reuses_the_smallest_operand() {
+        let large: RoaringBitmap = (0..10).map(|key| key << 16).collect();
+        let small: RoaringBitmap = [0, 3 << 16, 20 << 16].into_iter().collect();
+        let small_containers = small.containers.as_ptr();
+
+        let intersection = large.clone() & small.clone();
+        assert_eq!(intersection, RoaringBitmap::from([0, 3 << 16]));
+
+        let intersection = large & small;
+        assert_eq!(intersection.containers.as_ptr(), small_containers);
+    }
+
+    #[test]
+    fn into_reuses_the_output_containers() {
+        use crate::bitmap::store::Store;
+
+        let a: RoaringBitmap = (0..100_000).collect();
+        let b: RoaringBitmap = (50_000..150_000).collect();
+        let mut out = RoaringBitmap::new();
+        a.union_into(&b, &mut out);
+
+        let containers = out.containers.as_ptr();
+        let bits = match &out.containers[0].store {
+            Store::Bitmap(bits) => bits.as_array() as *const _,
+            Store::Array(_) => unreachable!(),
+        };
+
+        a.intersection_into(&b, &mut out);
+        assert_eq!(out, &a & &b);
+        assert_eq!(out.containers.as_ptr(), containers);
+        match &out.containers[0].store {
+            Store::Bitmap(store) => assert_eq!(store.as_array() as *const _, bits),
+            Store::Array(_) => unreachable!(),
+        }
+    }
 }
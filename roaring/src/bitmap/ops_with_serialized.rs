@@ -8,12 +8,13 @@ use std::ops::RangeInclusive;
 
 use crate::bitmap::container::Container;
 use crate::bitmap::serialization::{
-    NO_OFFSET_THRESHOLD, SERIAL_COOKIE, SERIAL_COOKIE_NO_RUNCONTAINER,
+    ContainerError, DESCRIPTION_BYTES, NO_OFFSET_THRESHOLD, OFFSET_BYTES, SERIAL_COOKIE,
+    SERIAL_COOKIE_NO_RUNCONTAINER,
 };
 use crate::RoaringBitmap;
 
 use super::container::ARRAY_LIMIT;
-use super::store::{ArrayStore, BitmapStore, Store, BITMAP_LENGTH};
+use super::store::{validate_sorted, ArrayStore, BitmapStore, Store, BITMAP_LENGTH};
 
 impl RoaringBitmap {
     /// Computes the intersection between a materialized [`RoaringBitmap`] and a serialized one.
@@ -75,13 +76,13 @@ impl RoaringBitmap {
                 let size = ((cookie >> 16) + 1) as usize;
                 (size, size >= NO_OFFSET_THRESHOLD, true)
             } else {
-                return Err(io::Error::new(io::ErrorKind::Other, "unknown cookie value"));
+                return Err(io::Error::other("unknown cookie value"));
             }
         };
 
         // Read the run container bitmap if necessary
         let run_container_bitmap = if has_run_containers {
-            let mut bitmap = vec![0u8; (size + 7) / 8];
+            let mut bitmap = vec![0u8; size.div_ceil(8)];
             reader.read_exact(&mut bitmap)?;
             Some(bitmap)
         } else {
@@ -89,7 +90,7 @@ impl RoaringBitmap {
         };
 
         if size > u16::MAX as usize + 1 {
-            return Err(io::Error::new(io::ErrorKind::Other, "size is greater than supported"));
+            return Err(io::Error::other("size is greater than supported"));
         }
 
         // Read the container descriptions
@@ -125,7 +126,7 @@ impl RoaringBitmap {
 
             // If the run container bitmap is present, check if this container is a run container
             let is_run_container =
-                run_container_bitmap.as_ref().map_or(false, |bm| bm[i / 8] & (1 << (i % 8)) != 0);
+                run_container_bitmap.as_ref().is_some_and(|bm| bm[i / 8] & (1 << (i % 8)) != 0);
 
             let store = if is_run_container {
                 let runs = reader.read_u16::<LittleEndian>()?;
@@ -232,7 +233,7 @@ impl RoaringBitmap {
 
             // If the run container bitmap is present, check if this container is a run container
             let is_run_container =
-                run_container_bitmap.as_ref().map_or(false, |bm| bm[i / 8] & (1 << (i % 8)) != 0);
+                run_container_bitmap.as_ref().is_some_and(|bm| bm[i / 8] & (1 << (i % 8)) != 0);
 
             let store = if is_run_container {
                 let runs = reader.read_u16::<LittleEndian>().unwrap();
@@ -275,6 +276,175 @@ impl RoaringBitmap {
 
         Ok(RoaringBitmap { containers })
     }
+
+    /// Computes the union between a materialized [`RoaringBitmap`] and a serialized one,
+    /// storing the result in `self`.
+    ///
+    /// The serialized containers are parsed and merged into `self` one by one, the
+    /// serialized bitmap is never deserialized as a whole. The containers merged into
+    /// a bitset container of `self` are read straight from the bytes, without any allocation.
+    ///
+    /// The serialized bitmap is checked like [`RoaringBitmap::deserialize_from`] does.
+    /// When an invalid container is found, an error is returned and `self` may contain
+    /// only a part of the serialized values.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    ///
+    /// // Let's say the rb2 bitmap is serialized
+    /// let mut bytes = Vec::new();
+    /// rb2.serialize_into(&mut bytes).unwrap();
+    ///
+    /// rb1.union_with_serialized(&bytes).unwrap();
+    /// assert_eq!(rb1, (1..5).collect());
+    /// ```
+    pub fn union_with_serialized(&mut self, mut bytes: &[u8]) -> io::Result<()> {
+        // First read the cookie to determine which version of the format we are reading
+        let (size, has_offsets, has_run_containers) = {
+            let cookie = bytes.read_u32::<LittleEndian>()?;
+            if cookie == SERIAL_COOKIE_NO_RUNCONTAINER {
+                (bytes.read_u32::<LittleEndian>()? as usize, true, false)
+            } else if (cookie as u16) == SERIAL_COOKIE {
+                let size = ((cookie >> 16) + 1) as usize;
+                (size, size >= NO_OFFSET_THRESHOLD, true)
+            } else {
+                return Err(io::Error::other("unknown cookie value"));
+            }
+        };
+
+        // Read the run container bitmap if necessary
+        let run_container_bitmap =
+            if has_run_containers { Some(take_bytes(&mut bytes, size.div_ceil(8))?) } else { None };
+
+        if size > u16::MAX as usize + 1 {
+            return Err(io::Error::other("size is greater than supported"));
+        }
+
+        // Read the container descriptions, the offsets are useless when reading sequentially
+        let descriptions = take_bytes(&mut bytes, size * DESCRIPTION_BYTES)?;
+        if has_offsets {
+            take_bytes(&mut bytes, size * OFFSET_BYTES)?;
+        }
+
+        // Check the keys before merging anything into `self`
+        let keys = || descriptions.chunks_exact(DESCRIPTION_BYTES).map(|d| [d[0], d[1]]);
+        let keys = || keys().map(u16::from_le_bytes);
+        if let Some((_, key)) = keys().zip(keys().skip(1)).find(|(prev, key)| prev >= key) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("container keys not strictly increasing at key {}", key),
+            ));
+        }
+
+        for (i, description) in descriptions.chunks_exact(DESCRIPTION_BYTES).enumerate() {
+            let key = u16::from_le_bytes([description[0], description[1]]);
+            let cardinality = u64::from(u16::from_le_bytes([description[2], description[3]])) + 1;
+
+            // If the run container bitmap is present, check if this container is a run container
+            let is_run_container =
+                run_container_bitmap.is_some_and(|bm| bm[i / 8] & (1 << (i % 8)) != 0);
+
+            let loc = match self.containers.binary_search_by_key(&key, |c| c.key) {
+                Ok(loc) => loc,
+                Err(loc) => {
+                    self.containers.insert(loc, Container::new(key));
+                    loc
+                }
+            };
+
+            let container = &mut self.containers[loc];
+            let result = union_with_serialized_container(
+                container,
+                &mut bytes,
+                cardinality,
+                is_run_container,
+            );
+
+            // A container inserted for an invalid serialized container can be left empty
+            if container.is_empty() {
+                self.containers.remove(loc);
+            }
+            result?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Merges the next serialized container of `bytes` into `container`.
+fn union_with_serialized_container(
+    container: &mut Container,
+    bytes: &mut &[u8],
+    cardinality: u64,
+    is_run_container: bool,
+) -> io::Result<()> {
+    let key = container.key;
+
+    let store = if is_run_container {
+        let runs = bytes.read_u16::<LittleEndian>()?;
+        for _ in 0..runs {
+            let start = bytes.read_u16::<LittleEndian>()?;
+            let len = bytes.read_u16::<LittleEndian>()?;
+            let end = start.checked_add(len).ok_or(io::ErrorKind::InvalidData)?;
+            container.insert_range(RangeInclusive::new(start, end));
+        }
+        return Ok(());
+    } else if cardinality <= ARRAY_LIMIT {
+        let values = take_bytes(bytes, mem::size_of::<u16>() * cardinality as usize)?;
+        let values = values.chunks_exact(mem::size_of::<u16>()).map(|v| [v[0], v[1]]);
+        let values = values.map(u16::from_le_bytes);
+        validate_sorted(values.clone()).map_err(|error| invalid_container(key, error))?;
+
+        if let Store::Bitmap(bitmap) = &mut container.store {
+            values.for_each(|value| {
+                bitmap.insert(value);
+            });
+            return Ok(());
+        }
+        Store::Array(ArrayStore::from_vec_unchecked(values.collect()))
+    } else {
+        let values = take_bytes(bytes, mem::size_of::<u64>() * BITMAP_LENGTH)?;
+        if let Store::Bitmap(bitmap) = &mut container.store {
+            return bitmap
+                .union_with_le_bytes(cardinality, values)
+                .map_err(|error| invalid_container(key, error));
+        }
+
+        let mut bits = Box::new([0; BITMAP_LENGTH]);
+        cast_slice_mut(&mut bits[..]).copy_from_slice(values);
+        bits.iter_mut().for_each(|n| *n = u64::from_le(*n));
+        Store::Bitmap(
+            BitmapStore::try_from(cardinality, bits)
+                .map_err(|error| invalid_container(key, error))?,
+        )
+    };
+
+    if container.is_empty() {
+        container.store = store;
+    } else {
+        *container |= Container { key, store };
+    }
+    Ok(())
+}
+
+/// Reports an invalid serialized container along with its key.
+fn invalid_container<E: Error + Send + Sync + 'static>(key: u16, error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, ContainerError { key, error })
+}
+
+/// Splits the first `n` bytes off `bytes`.
+fn take_bytes<'a>(bytes: &mut &'a [u8], n: usize) -> io::Result<&'a [u8]> {
+    if bytes.len() < n {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "failed to fill whole buffer"));
+    }
+    let (head, tail) = bytes.split_at(n);
+    *bytes = tail;
+    Ok(head)
 }
 
 #[cfg(test)]
@@ -296,5 +466,43 @@ mod test {
 
             prop_assert_eq!(a.intersection_with_serialized_unchecked(Cursor::new(serialized_bytes_b)).unwrap(), a & b);
         }
+
+        #[test]
+        fn union_with_serialized_eq_materialized_union(
+            a in RoaringBitmap::arbitrary(),
+            b in RoaringBitmap::arbitrary()
+        ) {
+            let mut serialized_bytes_b = Vec::new();
+            b.serialize_into(&mut serialized_bytes_b).unwrap();
+
+            let mut union = a.clone();
+            union.union_with_serialized(&serialized_bytes_b).unwrap();
+            prop_assert_eq!(union, a | b);
+        }
+    }
+
+    #[test]
+    fn union_with_serialized_invalid_container() {
+        let mut bitmap = RoaringBitmap::from([1, 2, 3]);
+
+        // An array container whose values are not sorted
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&12346u32.to_le_bytes());
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(&[5, 0, 1, 0]);
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&[2, 0, 1, 0]);
+
+        let error = bitmap.union_with_serialized(&bytes).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(bitmap, RoaringBitmap::from([1, 2, 3]));
+
+        // Truncated bytes
+        let mut bytes = Vec::new();
+        RoaringBitmap::from([1 << 20]).serialize_into(&mut bytes).unwrap();
+        bytes.pop();
+        let error = bitmap.union_with_serialized(&bytes).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+        assert_eq!(bitmap, RoaringBitmap::from([1, 2, 3]));
     }
 }
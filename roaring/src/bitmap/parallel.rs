@@ -0,0 +1,81 @@
+use rayon::prelude::*;
+
+use crate::RoaringBitmap;
+
+impl RoaringBitmap {
+    /// Returns a parallel iterator over the values of this bitmap.
+    ///
+    /// The work is split by containers, each thread iterates over a disjoint range of
+    /// keys. The values are yielded in ascending order when collected into an ordered
+    /// collection.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rayon::prelude::*;
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (0..1_000_000).collect();
+    /// let sum: u64 = rb.par_iter().map(u64::from).sum();
+    ///
+    /// assert_eq!(sum, rb.iter().map(u64::from).sum());
+    /// ```
+    pub fn par_iter(&self) -> impl ParallelIterator<Item = u32> + '_ {
+        self.containers.par_iter().flat_map_iter(|container| container.into_iter())
+    }
+}
+
+impl FromParallelIterator<u32> for RoaringBitmap {
+    /// Creates a bitmap from a parallel iterator of values.
+    ///
+    /// Each thread builds a partial bitmap from the values it receives, the partial
+    /// bitmaps are then unioned together in a tree reduction.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rayon::prelude::*;
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (0..1_000_000u32).into_par_iter().map(|i| i * 3).collect();
+    ///
+    /// assert_eq!(rb, (0..1_000_000u32).map(|i| i * 3).collect());
+    /// ```
+    fn from_par_iter<I>(par_iter: I) -> RoaringBitmap
+    where
+        I: IntoParallelIterator<Item = u32>,
+    {
+        par_iter
+            .into_par_iter()
+            .fold(RoaringBitmap::new, |mut bitmap, value| {
+                bitmap.insert(value);
+                bitmap
+            })
+            .reduce(RoaringBitmap::new, |lhs, rhs| lhs | rhs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::RoaringBitmap;
+    use proptest::prelude::*;
+    use rayon::prelude::*;
+
+    proptest! {
+        #[test]
+        fn par_iter_matches_iter(
+            bitmap in RoaringBitmap::arbitrary(),
+        ) {
+            let values: Vec<u32> = bitmap.par_iter().collect();
+            prop_assert_eq!(values, bitmap.iter().collect::<Vec<u32>>());
+        }
+
+        #[test]
+        fn par_collect_matches_collect(
+            values in prop::collection::vec(any::<u32>(), 0..10_000),
+        ) {
+            let bitmap: RoaringBitmap = values.par_iter().copied().collect();
+            prop_assert_eq!(bitmap, values.iter().copied().collect::<RoaringBitmap>());
+        }
+    }
+}
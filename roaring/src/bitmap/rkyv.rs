@@ -0,0 +1,95 @@
+use crate::bitmap::util;
+
+use super::ArchivedRoaringBitmap;
+
+/// The archived form of a [`RoaringBitmap`](crate::RoaringBitmap) mirrors its containers:
+/// a sorted list of keys, each one with either a sorted array of values or a bitset.
+/// It can be queried in place, without deserializing it.
+///
+/// The archived layout is tied to the internal representation of the bitmap and is only
+/// guaranteed to be readable by the version of this crate that wrote it. Use
+/// [`RoaringBitmap::serialize_into`](crate::RoaringBitmap::serialize_into) and the
+/// standard Roaring format to exchange bitmaps between versions or implementations.
+impl ArchivedRoaringBitmap {
+    /// Returns the number of distinct integers in the archived set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (1..4).collect();
+    /// let bytes = rkyv::to_bytes::<_, 256>(&rb).unwrap();
+    /// let archived = unsafe { rkyv::archived_root::<RoaringBitmap>(&bytes[..]) };
+    ///
+    /// assert_eq!(archived.len(), 3);
+    /// ```
+    pub fn len(&self) -> u64 {
+        self.containers.iter().map(|container| container.store.len()).sum()
+    }
+
+    /// Returns `true` if there are no integers in the archived set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let bytes = rkyv::to_bytes::<_, 256>(&RoaringBitmap::new()).unwrap();
+    /// let archived = unsafe { rkyv::archived_root::<RoaringBitmap>(&bytes[..]) };
+    ///
+    /// assert!(archived.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.containers.is_empty()
+    }
+
+    /// Returns `true` if the archived set contains this value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (1..4).collect();
+    /// let bytes = rkyv::to_bytes::<_, 256>(&rb).unwrap();
+    /// let archived = unsafe { rkyv::archived_root::<RoaringBitmap>(&bytes[..]) };
+    ///
+    /// assert!(archived.contains(3));
+    /// assert!(!archived.contains(4));
+    /// ```
+    pub fn contains(&self, value: u32) -> bool {
+        let (key, index) = util::split(value);
+        match self.containers.binary_search_by_key(&key, |container| container.key) {
+            Ok(loc) => self.containers[loc].store.contains(index),
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::RoaringBitmap;
+    use proptest::prelude::*;
+    use rkyv::Deserialize;
+
+    proptest! {
+        #[test]
+        fn test_rkyv(
+            bitmap in RoaringBitmap::arbitrary(),
+            values in prop::collection::vec(0u32..=262_143, 0..100),
+        ) {
+            let bytes = rkyv::to_bytes::<_, 4096>(&bitmap).unwrap();
+            let archived = unsafe { rkyv::archived_root::<RoaringBitmap>(&bytes[..]) };
+
+            prop_assert_eq!(archived.len(), bitmap.len());
+            prop_assert_eq!(archived.is_empty(), bitmap.is_empty());
+            for value in bitmap.iter().take(100).chain(values) {
+                prop_assert_eq!(archived.contains(value), bitmap.contains(value));
+            }
+
+            let deserialized: RoaringBitmap = archived.deserialize(&mut rkyv::Infallible).unwrap();
+            prop_assert_eq!(bitmap, deserialized);
+        }
+    }
+}
@@ -4,43 +4,67 @@ use serde::Deserialize;
 use serde::Deserializer;
 use serde::Serialize;
 
+use crate::bitmap::serialization::{SERIAL_COOKIE, SERIAL_COOKIE_NO_RUNCONTAINER};
 use crate::RoaringBitmap;
 
+/// The maximum number of values preallocated from the size hint of a sequence.
+const MAX_PREALLOCATED_VALUES: usize = 1024 * 1024 / core::mem::size_of::<u32>();
+
+struct BitmapVisitor;
+
+impl<'de> Visitor<'de> for BitmapVisitor {
+    type Value = RoaringBitmap;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("roaring bitmap")
+    }
+
+    fn visit_bytes<E>(self, bytes: &[u8]) -> Result<RoaringBitmap, E>
+    where
+        E: serde::de::Error,
+    {
+        RoaringBitmap::deserialize_from(bytes).map_err(serde::de::Error::custom)
+    }
+
+    // In some case bytes will be serialized as a sequence, and `serde_as_seq` writes the
+    // values as a sequence, thus we need to accept both. The serialized bytes always start
+    // with the cookie (`[58, 48, ..]` or `[59, 48, ..]`), any other sequence is read as
+    // values, in any order and with duplicates.
+    fn visit_seq<A>(self, mut seq: A) -> Result<RoaringBitmap, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        // The hint comes from the input, bound it like serde's `size_hint::cautious`
+        let capacity = seq.size_hint().unwrap_or(0).min(MAX_PREALLOCATED_VALUES);
+        let mut values: Vec<u32> = Vec::with_capacity(capacity);
+        while let Some(el) = seq.next_element()? {
+            values.push(el);
+        }
+
+        let starts_with_cookie = |cookie: u16| {
+            let [low, high] = cookie.to_le_bytes();
+            values.starts_with(&[u32::from(low), u32::from(high)])
+        };
+        if !starts_with_cookie(SERIAL_COOKIE_NO_RUNCONTAINER as u16)
+            && !starts_with_cookie(SERIAL_COOKIE)
+        {
+            return Ok(values.into_iter().collect());
+        }
+
+        let bytes = values
+            .into_iter()
+            .map(u8::try_from)
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(serde::de::Error::custom)?;
+        RoaringBitmap::deserialize_from(&*bytes).map_err(serde::de::Error::custom)
+    }
+}
+
 impl<'de> Deserialize<'de> for RoaringBitmap {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        struct BitmapVisitor;
-
-        impl<'de> Visitor<'de> for BitmapVisitor {
-            type Value = RoaringBitmap;
-
-            fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
-                formatter.write_str("roaring bitmap")
-            }
-
-            fn visit_bytes<E>(self, bytes: &[u8]) -> Result<RoaringBitmap, E>
-            where
-                E: serde::de::Error,
-            {
-                RoaringBitmap::deserialize_from(bytes).map_err(serde::de::Error::custom)
-            }
-
-            // in some case bytes will be serialized as a sequence thus we need to accept both
-            // even if it means non optimal performance
-            fn visit_seq<A>(self, mut seq: A) -> Result<RoaringBitmap, A::Error>
-            where
-                A: SeqAccess<'de>,
-            {
-                let mut bytes: Vec<u8> = Vec::new();
-                while let Some(el) = seq.next_element()? {
-                    bytes.push(el);
-                }
-                RoaringBitmap::deserialize_from(&*bytes).map_err(serde::de::Error::custom)
-            }
-        }
-
         deserializer.deserialize_bytes(BitmapVisitor)
     }
 }
@@ -50,13 +74,57 @@ impl Serialize for RoaringBitmap {
     where
         S: serde::Serializer,
     {
-        let mut buf = Vec::new();
+        let mut buf = Vec::with_capacity(self.serialized_size());
         self.serialize_into(&mut buf).map_err(serde::ser::Error::custom)?;
 
         serializer.serialize_bytes(&buf)
     }
 }
 
+/// Serializes a [`RoaringBitmap`] as a sequence of its values, to be used with
+/// `#[serde(with = "roaring::bitmap::serde_as_seq")]` on a field.
+///
+/// By default a bitmap is serialized as the bytes of the portable format, this module
+/// writes the values instead, which is easier to read in human readable formats.
+/// Both representations are accepted when deserializing, with or without this module.
+///
+/// # Examples
+///
+/// ```rust
+/// use roaring::bitmap::serde_as_seq;
+/// use roaring::RoaringBitmap;
+///
+/// let bitmap = RoaringBitmap::from([1, 2, 100_000]);
+/// let mut json = Vec::new();
+/// serde_as_seq::serialize(&bitmap, &mut serde_json::Serializer::new(&mut json)).unwrap();
+/// assert_eq!(json, b"[1,2,100000]");
+///
+/// let mut deserializer = serde_json::Deserializer::from_slice(&json);
+/// assert_eq!(serde_as_seq::deserialize(&mut deserializer).unwrap(), bitmap);
+/// ```
+pub mod serde_as_seq {
+    use serde::{Deserializer, Serializer};
+
+    use super::BitmapVisitor;
+    use crate::RoaringBitmap;
+
+    /// Serializes the values of the bitmap as a sequence.
+    pub fn serialize<S>(bitmap: &RoaringBitmap, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(bitmap)
+    }
+
+    /// Deserializes a bitmap from a sequence of values, or from the portable format.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<RoaringBitmap, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(BitmapVisitor)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::RoaringBitmap;
@@ -68,6 +136,12 @@ mod test {
             bitmap in RoaringBitmap::arbitrary(),
         ) {
             let json = serde_json::to_vec(&bitmap).unwrap();
+            prop_assert_eq!(&bitmap, &serde_json::from_slice(&json).unwrap());
+
+            // The portable bytes written by previous versions are still accepted
+            let mut bytes = Vec::new();
+            bitmap.serialize_into(&mut bytes).unwrap();
+            let json = serde_json::to_vec(&bytes).unwrap();
             prop_assert_eq!(bitmap, serde_json::from_slice(&json).unwrap());
         }
 
@@ -79,4 +153,47 @@ mod test {
             prop_assert_eq!(bitmap, bincode::deserialize(&buffer).unwrap());
         }
     }
+
+    #[test]
+    fn test_serde_json_default_is_bytes() {
+        let bitmap: RoaringBitmap = [1, 2, 3, 100_000].into_iter().collect();
+        let mut bytes = Vec::new();
+        bitmap.serialize_into(&mut bytes).unwrap();
+        assert_eq!(serde_json::to_string(&bitmap).unwrap(), serde_json::to_string(&bytes).unwrap());
+    }
+
+    fn to_seq(bitmap: &RoaringBitmap) -> String {
+        let mut json = Vec::new();
+        super::serde_as_seq::serialize(bitmap, &mut serde_json::Serializer::new(&mut json))
+            .unwrap();
+        String::from_utf8(json).unwrap()
+    }
+
+    fn from_seq(json: &str) -> serde_json::Result<RoaringBitmap> {
+        super::serde_as_seq::deserialize(&mut serde_json::Deserializer::from_str(json))
+    }
+
+    #[test]
+    fn test_serde_as_seq() {
+        let bitmap: RoaringBitmap = [1, 2, 3, 100_000].into_iter().collect();
+        let json = to_seq(&bitmap);
+        assert_eq!(json, "[1,2,3,100000]");
+        assert_eq!(bitmap, from_seq(&json).unwrap());
+        // The default deserialization accepts the values too
+        assert_eq!(bitmap, serde_json::from_str(&json).unwrap());
+
+        assert_eq!(to_seq(&RoaringBitmap::new()), "[]");
+        assert_eq!(RoaringBitmap::new(), serde_json::from_str("[]").unwrap());
+
+        // Values are accepted in any order and with duplicates
+        let bitmap = RoaringBitmap::from([3, 1000]);
+        assert_eq!(bitmap, serde_json::from_str("[1000, 3, 1000]").unwrap());
+        let bitmap = RoaringBitmap::from([0, 48, 58]);
+        assert_eq!(bitmap, from_seq("[0, 58, 48, 0]").unwrap());
+
+        // Only a sequence starting with the cookie is read as serialized bytes
+        let bytes = serde_json::to_string(&[58, 48, 0, 0, 0, 0, 0, 0]).unwrap();
+        assert_eq!(RoaringBitmap::new(), from_seq(&bytes).unwrap());
+        assert!(serde_json::from_str::<RoaringBitmap>("[58, 48, 1000]").is_err());
+    }
 }
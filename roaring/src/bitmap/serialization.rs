@@ -1,9 +1,10 @@
 use crate::bitmap::container::{Container, ARRAY_LIMIT};
 use crate::bitmap::store::{ArrayStore, BitmapStore, Store, BITMAP_LENGTH};
-use crate::RoaringBitmap;
+use crate::{LsbBytesError, RoaringBitmap};
 use bytemuck::cast_slice_mut;
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use core::convert::Infallible;
+use core::fmt;
 use core::mem::size_of;
 use core::ops::RangeInclusive;
 use std::error::Error;
@@ -16,6 +17,76 @@ pub const NO_OFFSET_THRESHOLD: usize = 4;
 // Sizes of header structures
 pub const DESCRIPTION_BYTES: usize = 4;
 pub const OFFSET_BYTES: usize = 4;
+pub const BITMAP_BYTES: usize = BITMAP_LENGTH * size_of::<u64>();
+
+/// The size in bytes of each section of a serialized bitmap,
+/// see [`RoaringBitmap::serialized_size_breakdown`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct SerializedSizeBreakdown {
+    /// Number of bytes used by the cookie, the container count and the container descriptions
+    pub header_bytes: usize,
+    /// Number of bytes used by the offset table
+    pub offset_bytes: usize,
+    /// Number of bytes used by array containers
+    pub array_bytes: usize,
+    /// Number of bytes used by bitset containers
+    pub bitmap_bytes: usize,
+    /// Number of bytes used by run containers
+    pub run_bytes: usize,
+}
+
+impl SerializedSizeBreakdown {
+    /// Returns the total size in bytes, the sum of every section.
+    pub fn total(&self) -> usize {
+        self.header_bytes
+            + self.offset_bytes
+            + self.array_bytes
+            + self.bitmap_bytes
+            + self.run_bytes
+    }
+}
+
+/// An error returned by [`RoaringBitmap::serialize_into_bounded`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SerializeError {
+    /// The serialized bitmap does not fit in the byte budget, nothing was written.
+    TooLarge {
+        /// Number of bytes required to serialize the bitmap
+        required: usize,
+        /// The byte budget
+        max_bytes: usize,
+    },
+    /// The writer failed, the output may be partially written.
+    Io(io::Error),
+}
+
+impl fmt::Display for SerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SerializeError::TooLarge { required, max_bytes } => {
+                write!(f, "serialized bitmap needs {required} bytes, the limit is {max_bytes}")
+            }
+            SerializeError::Io(error) => write!(f, "failed to write serialized bitmap: {error}"),
+        }
+    }
+}
+
+impl Error for SerializeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SerializeError::TooLarge { .. } => None,
+            SerializeError::Io(error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for SerializeError {
+    fn from(error: io::Error) -> Self {
+        SerializeError::Io(error)
+    }
+}
 
 impl RoaringBitmap {
     /// Return the size in bytes of the serialized output.
@@ -34,17 +105,70 @@ impl RoaringBitmap {
     /// assert_eq!(rb1, rb2);
     /// ```
     pub fn serialized_size(&self) -> usize {
-        let container_sizes: usize = self
-            .containers
-            .iter()
-            .map(|container| match container.store {
-                Store::Array(ref values) => 8 + values.len() as usize * 2,
-                Store::Bitmap(..) => 8 + 8 * 1024,
-            })
-            .sum();
+        self.serialized_size_breakdown().total()
+    }
 
-        // header + container sizes
-        8 + container_sizes
+    /// Return an upper bound of the size in bytes of the serialized output.
+    ///
+    /// Run containers are never written, so there is no run detection to skip and the
+    /// bound is exact, it is the same as [`RoaringBitmap::serialized_size`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (1..4).chain(100_000..200_000).collect();
+    /// assert_eq!(rb.serialized_size_upper_bound(), rb.serialized_size());
+    ///
+    /// let mut bytes = Vec::with_capacity(rb.serialized_size_upper_bound());
+    /// rb.serialize_into(&mut bytes).unwrap();
+    /// assert_eq!(bytes.len(), rb.serialized_size_upper_bound());
+    /// ```
+    pub fn serialized_size_upper_bound(&self) -> usize {
+        self.serialized_size()
+    }
+
+    /// Return the size in bytes of the serialized output, split by section
+    /// of the [standard Roaring on-disk format](https://github.com/RoaringBitmap/RoaringFormatSpec).
+    ///
+    /// The sum of the sections is [`RoaringBitmap::serialized_size`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb: RoaringBitmap = (1..4).collect();
+    /// rb.insert_range((1 << 16)..(2 << 16));
+    ///
+    /// let breakdown = rb.serialized_size_breakdown();
+    /// assert_eq!(breakdown.header_bytes, 16);
+    /// assert_eq!(breakdown.offset_bytes, 8);
+    /// assert_eq!(breakdown.array_bytes, 6);
+    /// assert_eq!(breakdown.bitmap_bytes, 8192);
+    /// assert_eq!(breakdown.run_bytes, 0);
+    /// assert_eq!(breakdown.total(), rb.serialized_size());
+    /// ```
+    pub fn serialized_size_breakdown(&self) -> SerializedSizeBreakdown {
+        let mut array_bytes = 0;
+        let mut bitmap_bytes = 0;
+        for container in &self.containers {
+            match container.store {
+                Store::Array(ref values) => array_bytes += values.len() as usize * 2,
+                Store::Bitmap(..) => bitmap_bytes += BITMAP_BYTES,
+            }
+        }
+
+        let n_containers = self.containers.len();
+        SerializedSizeBreakdown {
+            // cookie + container count + descriptions
+            header_bytes: 8 + n_containers * DESCRIPTION_BYTES,
+            offset_bytes: n_containers * OFFSET_BYTES,
+            array_bytes,
+            bitmap_bytes,
+            run_bytes: 0,
+        }
     }
 
     /// Creates a `RoaringBitmap` from a byte slice, interpreting the bytes as a bitmap with a specified offset.
@@ -52,7 +176,7 @@ impl RoaringBitmap {
     /// # Arguments
     ///
     /// - `offset: u32` - The starting position in the bitmap where the byte slice will be applied, specified in bits.
-    ///                   This means that if `offset` is `n`, the first byte in the slice will correspond to the `n`th bit(0-indexed) in the bitmap.
+    ///   This means that if `offset` is `n`, the first byte in the slice will correspond to the `n`th bit(0-indexed) in the bitmap.
     /// - `bytes: &[u8]` - The byte slice containing the bitmap data. The bytes are interpreted in "Least-Significant-First" bit order.
     ///
     /// # Interpretation of `bytes`
@@ -63,7 +187,8 @@ impl RoaringBitmap {
     ///
     /// # Panics
     ///
-    /// This function will panic if `bytes.len() + offset` is greater than 2^32.
+    /// This function will panic if `bytes.len() * 8 + offset` is greater than 2^32,
+    /// see [`RoaringBitmap::try_from_lsb0_bytes`] for a non-panicking version.
     ///
     ///
     /// # Examples
@@ -95,7 +220,91 @@ impl RoaringBitmap {
     /// assert!(rb.contains(12));
     /// assert!(rb.contains(34));
     /// ```
-    pub fn from_lsb0_bytes(offset: u32, mut bytes: &[u8]) -> RoaringBitmap {
+    pub fn from_lsb0_bytes(offset: u32, bytes: &[u8]) -> RoaringBitmap {
+        Self::try_from_lsb0_bytes(offset, bytes).expect("offset + bytes.len() * 8 must be <= 2^32")
+    }
+
+    /// Creates a `RoaringBitmap` from a byte slice, interpreting the bytes as a bitmap with a specified offset.
+    ///
+    /// This is the non-panicking version of [`RoaringBitmap::from_lsb0_bytes`], the bytes are
+    /// interpreted the same way and may span any number of containers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes.len() * 8 + offset` is greater than 2^32.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let bytes = [0b00000101, 0b10000000];
+    /// let rb = RoaringBitmap::try_from_lsb0_bytes(u32::MAX - 15, &bytes).unwrap();
+    /// assert_eq!(rb.iter().collect::<Vec<u32>>(), [u32::MAX - 15, u32::MAX - 13, u32::MAX]);
+    ///
+    /// assert!(RoaringBitmap::try_from_lsb0_bytes(u32::MAX - 14, &bytes).is_err());
+    /// ```
+    pub fn try_from_lsb0_bytes(offset: u32, bytes: &[u8]) -> Result<RoaringBitmap, LsbBytesError> {
+        let len_bits = (bytes.len() as u64).checked_mul(8);
+        match len_bits.and_then(|len_bits| len_bits.checked_add(u64::from(offset))) {
+            Some(end_bit) if end_bit <= 1 << 32 => {
+                Ok(Self::from_lsb0_bytes_unchecked(offset, bytes))
+            }
+            _ => Err(LsbBytesError { offset, len: bytes.len() }),
+        }
+    }
+
+    /// Creates a `RoaringBitmap` from a slice of `u64` words, where the bit `j` of the word `i`
+    /// represents the value `base + i * 64 + j`.
+    ///
+    /// Any `base` is accepted, but when it is a multiple of 65536 each group of 1024 words is
+    /// the exact content of a container, bitset containers are then populated by copying the
+    /// words directly. Other bases go through [`RoaringBitmap::from_lsb0_bytes`].
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `words.len() * 64 + base` is greater than 2^32.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let words = [0b101, 0, 1 << 63];
+    /// let rb = RoaringBitmap::from_word_slice(&words, 0);
+    /// assert_eq!(rb.iter().collect::<Vec<u32>>(), [0, 2, 191]);
+    ///
+    /// let rb = RoaringBitmap::from_word_slice(&words, 1 << 16);
+    /// assert_eq!(rb.iter().collect::<Vec<u32>>(), [65536, 65538, 65727]);
+    ///
+    /// let rb = RoaringBitmap::from_word_slice(&words, 3);
+    /// assert_eq!(rb.iter().collect::<Vec<u32>>(), [3, 5, 194]);
+    /// ```
+    pub fn from_word_slice(words: &[u64], base: u32) -> RoaringBitmap {
+        let len_bits = (words.len() as u64).checked_mul(64);
+        let end_bit = len_bits.and_then(|len_bits| len_bits.checked_add(u64::from(base)));
+        assert!(
+            end_bit.is_some_and(|end_bit| end_bit <= 1 << 32),
+            "base + words.len() * 64 must be <= 2^32"
+        );
+
+        if base % 0x1_0000 != 0 {
+            let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+            return RoaringBitmap::from_lsb0_bytes_unchecked(base, &bytes);
+        }
+
+        let first_key = (base >> 16) as usize;
+        let containers = words
+            .chunks(BITMAP_LENGTH)
+            .enumerate()
+            .filter_map(|(index, words)| Container::from_words((first_key + index) as u16, words))
+            .collect();
+
+        RoaringBitmap { containers }
+    }
+
+    /// Creates a `RoaringBitmap` from a byte slice that is known to fit at `offset`.
+    fn from_lsb0_bytes_unchecked(offset: u32, mut bytes: &[u8]) -> RoaringBitmap {
         fn shift_bytes(bytes: &[u8], amount: usize) -> Vec<u8> {
             let mut result = Vec::with_capacity(bytes.len() + 1);
             let mut carry = 0u8;
@@ -115,7 +324,7 @@ impl RoaringBitmap {
         if offset % 8 != 0 {
             let shift = offset as usize % 8;
             let shifted_bytes = shift_bytes(bytes, shift);
-            return RoaringBitmap::from_lsb0_bytes(offset - shift as u32, &shifted_bytes);
+            return RoaringBitmap::from_lsb0_bytes_unchecked(offset - shift as u32, &shifted_bytes);
         }
 
         if bytes.is_empty() {
@@ -128,7 +337,7 @@ impl RoaringBitmap {
             .and_then(|len_bytes| len_bytes.checked_mul(8))
             // `bytes` is non-empty, so len_bits is > 0
             .and_then(|len_bits| offset.checked_add(len_bits - 1))
-            .expect("offset + bytes.len() must be <= 2^32");
+            .expect("offset + bytes.len() * 8 must be <= 2^32");
 
         // offsets are in bytes
         let (mut start_container, start_offset) =
@@ -237,12 +446,61 @@ impl RoaringBitmap {
         Ok(())
     }
 
+    /// Serialize this bitmap like [`RoaringBitmap::serialize_into`], only if it fits in
+    /// `max_bytes`, and returns the number of bytes written.
+    ///
+    /// The size is checked up front with [`RoaringBitmap::serialized_size`], so nothing is
+    /// written when the bitmap does not fit. This size depends on the current representation of
+    /// the containers, it is the one that will be written.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SerializeError::TooLarge`] with the required size if the bitmap does not fit,
+    /// or [`SerializeError::Io`] if the writer fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::bitmap::SerializeError;
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (1..4).collect();
+    /// let mut bytes = vec![];
+    /// let written = rb.serialize_into_bounded(&mut bytes, 64).unwrap();
+    /// assert_eq!(written, bytes.len());
+    /// assert_eq!(written, rb.serialized_size());
+    ///
+    /// let mut bytes = vec![];
+    /// match rb.serialize_into_bounded(&mut bytes, 16) {
+    ///     Err(SerializeError::TooLarge { required, .. }) => assert_eq!(required, 22),
+    ///     _ => unreachable!(),
+    /// }
+    /// assert!(bytes.is_empty());
+    /// ```
+    pub fn serialize_into_bounded<W: io::Write>(
+        &self,
+        writer: W,
+        max_bytes: usize,
+    ) -> Result<usize, SerializeError> {
+        let required = self.serialized_size();
+        if required > max_bytes {
+            return Err(SerializeError::TooLarge { required, max_bytes });
+        }
+
+        self.serialize_into(writer)?;
+        Ok(required)
+    }
+
     /// Deserialize a bitmap into memory from [the standard Roaring on-disk
     /// format][format]. This is compatible with the official C/C++, Java and
     /// Go implementations. This method checks that all of the internal values
     /// are valid. If deserializing from a trusted source consider
     /// [RoaringBitmap::deserialize_unchecked_from]
     ///
+    /// When the data is invalid the returned error has the
+    /// [`io::ErrorKind::InvalidData`] kind and describes which container
+    /// key is corrupt and what invariant it fails.
+    ///
     /// [format]: https://github.com/RoaringBitmap/RoaringFormatSpec
     ///
     /// # Examples
@@ -258,7 +516,20 @@ impl RoaringBitmap {
     /// assert_eq!(rb1, rb2);
     /// ```
     pub fn deserialize_from<R: io::Read>(reader: R) -> io::Result<RoaringBitmap> {
-        RoaringBitmap::deserialize_from_impl(reader, ArrayStore::try_from, BitmapStore::try_from)
+        let bitmap = RoaringBitmap::deserialize_from_impl(
+            reader,
+            ArrayStore::try_from,
+            BitmapStore::try_from,
+        )?;
+
+        if let Some(pair) = bitmap.containers.windows(2).find(|pair| pair[0].key >= pair[1].key) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("container keys not strictly increasing at key {}", pair[1].key),
+            ));
+        }
+
+        Ok(bitmap)
     }
 
     /// Deserialize a bitmap into memory from [the standard Roaring on-disk
@@ -309,13 +580,13 @@ impl RoaringBitmap {
                 let size = ((cookie >> 16) + 1) as usize;
                 (size, size >= NO_OFFSET_THRESHOLD, true)
             } else {
-                return Err(io::Error::new(io::ErrorKind::Other, "unknown cookie value"));
+                return Err(io::Error::other("unknown cookie value"));
             }
         };
 
         // Read the run container bitmap if necessary
         let run_container_bitmap = if has_run_containers {
-            let mut bitmap = vec![0u8; (size + 7) / 8];
+            let mut bitmap = vec![0u8; size.div_ceil(8)];
             reader.read_exact(&mut bitmap)?;
             Some(bitmap)
         } else {
@@ -323,7 +594,7 @@ impl RoaringBitmap {
         };
 
         if size > u16::MAX as usize + 1 {
-            return Err(io::Error::new(io::ErrorKind::Other, "size is greater than supported"));
+            return Err(io::Error::other("size is greater than supported"));
         }
 
         // Read the container descriptions
@@ -346,7 +617,7 @@ impl RoaringBitmap {
 
             // If the run container bitmap is present, check if this container is a run container
             let is_run_container =
-                run_container_bitmap.as_ref().map_or(false, |bm| bm[i / 8] & (1 << (i % 8)) != 0);
+                run_container_bitmap.as_ref().is_some_and(|bm| bm[i / 8] & (1 << (i % 8)) != 0);
 
             let store = if is_run_container {
                 let runs = reader.read_u16::<LittleEndian>()?;
@@ -369,14 +640,17 @@ impl RoaringBitmap {
                 let mut values = vec![0; cardinality as usize];
                 reader.read_exact(cast_slice_mut(&mut values))?;
                 values.iter_mut().for_each(|n| *n = u16::from_le(*n));
-                let array = a(values).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                let array = a(values).map_err(|error| {
+                    io::Error::new(io::ErrorKind::InvalidData, ContainerError { key, error })
+                })?;
                 Store::Array(array)
             } else {
                 let mut values = Box::new([0; BITMAP_LENGTH]);
                 reader.read_exact(cast_slice_mut(&mut values[..]))?;
                 values.iter_mut().for_each(|n| *n = u64::from_le(*n));
-                let bitmap = b(cardinality, values)
-                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                let bitmap = b(cardinality, values).map_err(|error| {
+                    io::Error::new(io::ErrorKind::InvalidData, ContainerError { key, error })
+                })?;
                 Store::Bitmap(bitmap)
             };
 
@@ -387,8 +661,65 @@ impl RoaringBitmap {
     }
 }
 
+/// Deserializes a bitmap from [the standard Roaring on-disk format][format]
+/// using [`RoaringBitmap::deserialize_from`].
+///
+/// [format]: https://github.com/RoaringBitmap/RoaringFormatSpec
+///
+/// # Examples
+///
+/// ```rust
+/// use roaring::RoaringBitmap;
+///
+/// let rb1: RoaringBitmap = (1..4).collect();
+/// let mut bytes = vec![];
+/// rb1.serialize_into(&mut bytes).unwrap();
+/// let rb2 = RoaringBitmap::try_from(&bytes[..]).unwrap();
+///
+/// assert_eq!(rb1, rb2);
+/// ```
+impl TryFrom<&[u8]> for RoaringBitmap {
+    type Error = io::Error;
+
+    fn try_from(bytes: &[u8]) -> io::Result<RoaringBitmap> {
+        RoaringBitmap::deserialize_from(bytes)
+    }
+}
+
+/// Deserializes a bitmap from [the standard Roaring on-disk format][format]
+/// using [`RoaringBitmap::deserialize_from`].
+///
+/// [format]: https://github.com/RoaringBitmap/RoaringFormatSpec
+impl TryFrom<Vec<u8>> for RoaringBitmap {
+    type Error = io::Error;
+
+    fn try_from(bytes: Vec<u8>) -> io::Result<RoaringBitmap> {
+        RoaringBitmap::deserialize_from(bytes.as_slice())
+    }
+}
+
+/// An invalid store found while deserializing, along with the key of its container.
+#[derive(Debug)]
+pub(crate) struct ContainerError<E> {
+    pub(crate) key: u16,
+    pub(crate) error: E,
+}
+
+impl<E: fmt::Display> fmt::Display for ContainerError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid container at key {}: {}", self.key, self.error)
+    }
+}
+
+impl<E: Error + 'static> Error for ContainerError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use crate::bitmap::container::ARRAY_LIMIT;
     use crate::{bitmap::store::BITMAP_LENGTH, RoaringBitmap};
     use proptest::prelude::*;
 
@@ -403,6 +734,68 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_try_from_bytes() {
+        let bitmap: RoaringBitmap = (0..5000).chain(100_000..100_010).collect();
+        let mut buffer = Vec::new();
+        bitmap.serialize_into(&mut buffer).unwrap();
+
+        assert_eq!(RoaringBitmap::try_from(buffer.as_slice()).unwrap(), bitmap);
+        assert_eq!(RoaringBitmap::try_from(buffer.clone()).unwrap(), bitmap);
+
+        buffer.truncate(buffer.len() - 1);
+        assert!(RoaringBitmap::try_from(buffer).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_reports_invalid_array_key() {
+        let bitmap: RoaringBitmap = (1..4).map(|i| (42 << 16) + i).collect();
+        let mut buffer = Vec::new();
+        bitmap.serialize_into(&mut buffer).unwrap();
+
+        // The array values start after the cookie, the size, the description and the offset.
+        buffer[16..18].copy_from_slice(&3u16.to_le_bytes());
+
+        let error = RoaringBitmap::deserialize_from(buffer.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(
+            error.to_string(),
+            "invalid container at key 42: An element was out of order at index: 1"
+        );
+    }
+
+    #[test]
+    fn test_deserialize_reports_invalid_bitmap_key() {
+        let bitmap: RoaringBitmap = (0..5000).map(|i| (7 << 16) + i).collect();
+        let mut buffer = Vec::new();
+        bitmap.serialize_into(&mut buffer).unwrap();
+
+        // Clear the first word of the bitmap so that the cardinality no longer matches.
+        buffer[16..24].copy_from_slice(&0u64.to_le_bytes());
+
+        let error = RoaringBitmap::deserialize_from(buffer.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(
+            error.to_string(),
+            "invalid container at key 7: Expected cardinality was 5000 but was 4936"
+        );
+    }
+
+    #[test]
+    fn test_deserialize_reports_unordered_keys() {
+        let bitmap: RoaringBitmap = [1 << 16, 2 << 16].into_iter().collect();
+        let mut buffer = Vec::new();
+        bitmap.serialize_into(&mut buffer).unwrap();
+
+        // Swap the keys of both container descriptions.
+        buffer[8..10].copy_from_slice(&2u16.to_le_bytes());
+        buffer[12..14].copy_from_slice(&1u16.to_le_bytes());
+
+        let error = RoaringBitmap::deserialize_from(buffer.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "container keys not strictly increasing at key 1");
+    }
+
     #[test]
     fn test_from_lsb0_bytes() {
         const CONTAINER_OFFSET: u32 = u64::BITS * BITMAP_LENGTH as u32;
@@ -478,6 +871,102 @@ mod test {
         RoaringBitmap::from_lsb0_bytes(u32::MAX - 7, &bytes);
     }
 
+    #[test]
+    fn test_try_from_lsb0_bytes() {
+        // Spans the end of a first container, a full container and the start of a third one
+        let mut bytes = vec![0; 0x3000 + 2];
+        bytes[0] = 0b1000_0001;
+        bytes[0x1000] = 0b0000_0010;
+        bytes[0x3000 + 1] = 0b1000_0000;
+
+        let offset = 0x1_0000 - 0x1000 * 8;
+        let rb = RoaringBitmap::try_from_lsb0_bytes(offset, &bytes).unwrap();
+        let expected = [offset, offset + 7, 0x1_0001, 0x2_0000 + 15];
+        assert_eq!(rb.iter().collect::<Vec<u32>>(), expected);
+        assert_eq!(rb, RoaringBitmap::from_lsb0_bytes(offset, &bytes));
+
+        let rb = RoaringBitmap::try_from_lsb0_bytes(u32::MAX - 7, &[0x80]).unwrap();
+        assert_eq!(rb.iter().collect::<Vec<u32>>(), [u32::MAX]);
+        assert!(RoaringBitmap::try_from_lsb0_bytes(u32::MAX, &[]).unwrap().is_empty());
+
+        let error = RoaringBitmap::try_from_lsb0_bytes(u32::MAX - 7, &[0x01, 0x01]).unwrap_err();
+        assert_eq!((error.offset(), error.bytes_len()), (u32::MAX - 7, 2));
+        assert!(RoaringBitmap::try_from_lsb0_bytes(u32::MAX - 6, &[0x01]).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn test_from_word_slice_eq_from_lsb0_bytes(
+            words in prop::collection::vec(any::<u64>(), 0..2500),
+            dense in 0usize..2500,
+            base in prop_oneof![0u32..4, (0u32..4).prop_map(|key| key << 16), 0u32..0x4_0000],
+        ) {
+            let mut words = words;
+            // Make some containers dense enough to be bitsets
+            for word in words.iter_mut().skip(dense).take(BITMAP_LENGTH) {
+                *word = u64::MAX;
+            }
+
+            let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+            let rb = RoaringBitmap::from_word_slice(&words, base);
+            prop_assert_eq!(rb.validate(), Ok(()));
+            prop_assert_eq!(&rb, &RoaringBitmap::from_lsb0_bytes(base, &bytes));
+            prop_assert_eq!(rb.len(), words.iter().map(|word| u64::from(word.count_ones())).sum::<u64>());
+        }
+    }
+
+    #[test]
+    fn test_from_word_slice() {
+        // An array container, an empty one that is skipped, and a bitset one
+        let mut words = vec![0; BITMAP_LENGTH * 3];
+        words[0] = 0b101;
+        words[BITMAP_LENGTH - 1] = 1 << 63;
+        words[BITMAP_LENGTH * 2..].fill(u64::MAX);
+
+        let rb = RoaringBitmap::from_word_slice(&words, 0x1_0000);
+        let mut expected = RoaringBitmap::from([0x1_0000, 0x1_0002, 0x1_FFFF]);
+        expected.insert_range(0x3_0000..0x4_0000);
+        assert_eq!(rb, expected);
+        assert_eq!(rb.statistics().n_containers, 2);
+
+        // The last word of the value space
+        let rb = RoaringBitmap::from_word_slice(&[1 << 63], u32::MAX - 63);
+        assert_eq!(rb.iter().collect::<Vec<u32>>(), [u32::MAX]);
+        let rb = RoaringBitmap::from_word_slice(&vec![u64::MAX; BITMAP_LENGTH], 0xFFFF_0000);
+        assert_eq!(rb.len(), 0x1_0000);
+        assert!(RoaringBitmap::from_word_slice(&[], u32::MAX).is_empty());
+    }
+
+    #[test]
+    fn test_from_words_and_bytes_array_limit() {
+        // A container of exactly ARRAY_LIMIT values is an array, one more value makes it a bitset
+        for (len, n_array_containers) in [(ARRAY_LIMIT, 1), (ARRAY_LIMIT + 1, 0)] {
+            let mut words = vec![0u64; BITMAP_LENGTH];
+            for i in 0..len as usize {
+                words[i / 64] |= 1 << (i % 64);
+            }
+            let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+
+            let from_words = RoaringBitmap::from_word_slice(&words, 0);
+            let from_bytes = RoaringBitmap::from_lsb0_bytes(0, &bytes);
+            for rb in [&from_words, &from_bytes] {
+                assert_eq!(rb.len(), len);
+                assert_eq!(rb.statistics().n_array_containers, n_array_containers);
+            }
+            assert_eq!(from_words, from_bytes);
+
+            let mut serialized = Vec::new();
+            from_words.serialize_into(&mut serialized).unwrap();
+            assert_eq!(RoaringBitmap::deserialize_from(&serialized[..]).unwrap(), from_words);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "<= 2^32")]
+    fn test_from_word_slice_overflow() {
+        RoaringBitmap::from_word_slice(&[1, 1], u32::MAX - 64);
+    }
+
     #[test]
     fn test_deserialize_overflow_s_plus_len() {
         let data = vec![59, 48, 0, 0, 255, 130, 254, 59, 48, 2, 0, 41, 255, 255, 166, 197, 4, 0, 2];
@@ -37,6 +37,38 @@ pub struct Statistics {
     pub cardinality: u64,
 }
 
+/// The kind of a container, see [`ContainerInfo`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum ContainerKind {
+    /// A sorted array of values, used for sparse containers
+    Array,
+    /// A bitset of 2^16 bits, used for dense containers
+    Bitset,
+}
+
+/// Metadata about a single container of a bitmap.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct ContainerInfo {
+    /// The high 16 bits shared by every value of the container
+    pub key: u16,
+    /// The kind of the container
+    pub kind: ContainerKind,
+    /// Number of values stored in the container
+    pub len: u64,
+}
+
+/// The outcome of [`RoaringBitmap::insert_with_outcome`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct InsertOutcome {
+    /// Whether the value was not already present in the bitmap
+    pub was_new: bool,
+    /// The kinds of the container before and after the insertion, if it was converted
+    pub container_converted: Option<(ContainerKind, ContainerKind)>,
+}
+
 impl RoaringBitmap {
     /// Returns statistics about the composition of a roaring bitmap.
     ///
@@ -104,4 +136,69 @@ impl RoaringBitmap {
             cardinality,
         }
     }
+
+    /// Returns the number of containers of this bitmap.
+    ///
+    /// ```
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let bitmap: RoaringBitmap = [1, 2, 1 << 16, 3 << 16].into_iter().collect();
+    /// assert_eq!(bitmap.container_count(), 3);
+    /// ```
+    pub fn container_count(&self) -> usize {
+        self.containers.len()
+    }
+
+    /// Returns an iterator over the metadata of the containers of this bitmap,
+    /// in ascending key order.
+    ///
+    /// This is lighter than [`RoaringBitmap::statistics`] when only the
+    /// layout of the containers is of interest.
+    ///
+    /// ```
+    /// use roaring::bitmap::ContainerKind;
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut bitmap: RoaringBitmap = (0..10).collect();
+    /// bitmap.insert_range((1 << 16)..(2 << 16));
+    ///
+    /// let infos: Vec<_> = bitmap.containers().map(|info| (info.key, info.kind, info.len)).collect();
+    /// assert_eq!(infos, [(0, ContainerKind::Array, 10), (1, ContainerKind::Bitset, 65536)]);
+    /// ```
+    pub fn containers(&self) -> impl Iterator<Item = ContainerInfo> + '_ {
+        self.containers.iter().map(|Container { key, store }| ContainerInfo {
+            key: *key,
+            kind: store.kind(),
+            len: store.len(),
+        })
+    }
+
+    /// Returns the 1024 words of the container of the given key when it is a bitset,
+    /// `None` when there is no such container or when it is an array.
+    ///
+    /// The bit `i % 64` of the word `i / 64` is set when `(key << 16) + i` is in the bitmap.
+    /// This is an escape hatch to run custom kernels over the dense containers: the words
+    /// are borrowed from the bitmap, any modification can turn the container into an array
+    /// and must be followed by a new call.
+    ///
+    /// ```
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut bitmap: RoaringBitmap = (0..10).collect();
+    /// bitmap.insert_range((1 << 16) + 64..(2 << 16));
+    ///
+    /// assert_eq!(bitmap.container_words(0), None);
+    /// assert_eq!(bitmap.container_words(2), None);
+    ///
+    /// let words = bitmap.container_words(1).unwrap();
+    /// assert_eq!(words.len(), 1024);
+    /// assert_eq!((words[0], words[1]), (0, u64::MAX));
+    /// ```
+    pub fn container_words(&self, key: u16) -> Option<&[u64]> {
+        let loc = self.containers.binary_search_by_key(&key, |container| container.key).ok()?;
+        match &self.containers[loc].store {
+            Store::Bitmap(bits) => Some(&bits.as_array()[..]),
+            Store::Array(_) => None,
+        }
+    }
 }
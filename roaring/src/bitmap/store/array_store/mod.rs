@@ -16,12 +16,24 @@ use alloc::vec::Vec;
 use alloc::boxed::Box;
 
 use super::bitmap_store::{bit, key, BitmapStore, BITMAP_LENGTH};
+use crate::bitmap::container::ARRAY_LIMIT;
 
-#[derive(Clone, Eq, PartialEq)]
+#[derive(Eq, PartialEq)]
+#[cfg_attr(feature = "rkyv", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
 pub struct ArrayStore {
     vec: Vec<u16>,
 }
 
+impl Clone for ArrayStore {
+    fn clone(&self) -> Self {
+        ArrayStore { vec: self.vec.clone() }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.vec.clone_from(&source.vec);
+    }
+}
+
 impl ArrayStore {
     pub fn new() -> ArrayStore {
         ArrayStore { vec: vec![] }
@@ -36,6 +48,22 @@ impl ArrayStore {
         self.vec.capacity()
     }
 
+    /// Makes room for `additional` more values, doubling the capacity like `Vec` does but
+    /// without growing past `ARRAY_LIMIT`, as the container becomes a bitmap beyond that.
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        let len = self.vec.len();
+        let required = len + additional;
+        // The store is about to be converted to a bitmap, growing the array would be wasted
+        if required > ARRAY_LIMIT as usize {
+            return;
+        }
+        if required > self.vec.capacity() {
+            let doubled = (self.vec.capacity() * 2).max(4).min(ARRAY_LIMIT as usize);
+            self.vec.reserve_exact(doubled.max(required) - len);
+        }
+    }
+
     ///
     /// Create a new SortedU16Vec from a given vec
     /// It is up to the caller to ensure the vec is sorted and deduplicated
@@ -80,9 +108,41 @@ impl ArrayStore {
         Self::from_vec_unchecked(vec)
     }
 
+    pub fn from_words(words: &[u64], bits_set: u64) -> Self {
+        let mut vec = Vec::with_capacity(bits_set as usize);
+
+        for (index, mut word) in words.iter().copied().enumerate() {
+            while word != 0 {
+                vec.push((index * 64) as u16 + word.trailing_zeros() as u16);
+                word &= word - 1;
+            }
+        }
+
+        Self::from_vec_unchecked(vec)
+    }
+
     #[inline]
     pub fn insert(&mut self, index: u16) -> bool {
-        self.vec.binary_search(&index).map_err(|loc| self.vec.insert(loc, index)).is_err()
+        self.vec
+            .binary_search(&index)
+            .map_err(|loc| {
+                self.reserve(1);
+                self.vec.insert(loc, index)
+            })
+            .is_err()
+    }
+
+    /// Inserts the index and returns whether it was new along with its rank,
+    /// from the position found by a single search.
+    pub fn insert_and_rank(&mut self, index: u16) -> (bool, u64) {
+        match self.vec.binary_search(&index) {
+            Ok(loc) => (false, loc as u64 + 1),
+            Err(loc) => {
+                self.reserve(1);
+                self.vec.insert(loc, index);
+                (true, loc as u64 + 1)
+            }
+        }
     }
 
     pub fn insert_range(&mut self, range: RangeInclusive<u16>) -> u64 {
@@ -97,6 +157,9 @@ impl ArrayStore {
                 Err(x) => x,
             };
 
+        let range_len = end as usize - start as usize + 1;
+        self.reserve(range_len.saturating_sub(pos_end - pos_start));
+
         // Overwrite the range in the middle - there's no need to take
         // into account any existing elements between start and end, as
         // they're all being added to the set.
@@ -107,6 +170,7 @@ impl ArrayStore {
 
     pub fn push(&mut self, index: u16) -> bool {
         if self.max().map_or(true, |max| max < index) {
+            self.reserve(1);
             self.vec.push(index);
             true
         } else {
@@ -127,6 +191,7 @@ impl ArrayStore {
                 assert!(index > max, "store max >= index")
             }
         }
+        self.reserve(1);
         self.vec.push(index);
     }
 
@@ -149,13 +214,39 @@ impl ArrayStore {
         (pos_end - pos_start) as u64
     }
 
+    /// Flips every value of the range, returns the number of added values
+    /// minus the number of removed values.
+    pub fn flip_range(&mut self, range: RangeInclusive<u16>) -> i64 {
+        let start = *range.start();
+        let end = *range.end();
+
+        // Figure out the starting/ending position in the vec.
+        let pos_start = self.vec.binary_search(&start).unwrap_or_else(|x| x);
+        let pos_end = pos_start
+            + match self.vec[pos_start..].binary_search(&end) {
+                Ok(x) => x + 1,
+                Err(x) => x,
+            };
+
+        // Replace the values in the range by the ones that were missing.
+        let mut present = self.vec[pos_start..pos_end].iter().peekable();
+        let flipped: Vec<u16> =
+            (start..=end).filter(|value| present.next_if_eq(&value).is_none()).collect();
+
+        let removed = (pos_end - pos_start) as i64;
+        let added = flipped.len() as i64;
+        self.vec.splice(pos_start..pos_end, flipped);
+        added - removed
+    }
+
     pub fn remove_smallest(&mut self, n: u64) {
-        self.vec.rotate_left(n as usize);
-        self.vec.truncate(self.vec.len() - n as usize);
+        let n = usize::try_from(n).unwrap_or(usize::MAX).min(self.vec.len());
+        self.vec.drain(..n);
     }
 
     pub fn remove_biggest(&mut self, n: u64) {
-        self.vec.truncate(self.vec.len() - n as usize);
+        let n = usize::try_from(n).unwrap_or(usize::MAX).min(self.vec.len());
+        self.vec.truncate(self.vec.len() - n);
     }
 
     pub fn contains(&self, index: u16) -> bool {
@@ -258,7 +349,11 @@ impl ArrayStore {
         self.vec.get(n as usize).cloned()
     }
 
-    pub fn iter(&self) -> core::slice::Iter<u16> {
+    pub fn runs(&self) -> ArrayRuns<'_> {
+        ArrayRuns { values: &self.vec }
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, u16> {
         self.vec.iter()
     }
 
@@ -288,12 +383,45 @@ impl ArrayStore {
     }
 }
 
+#[cfg(feature = "rkyv")]
+impl ArchivedArrayStore {
+    pub fn len(&self) -> u64 {
+        self.vec.len() as u64
+    }
+
+    pub fn contains(&self, index: u16) -> bool {
+        self.vec.binary_search(&index).is_ok()
+    }
+}
+
 impl Default for ArrayStore {
     fn default() -> Self {
         ArrayStore::new()
     }
 }
 
+/// An iterator over the maximal runs of consecutive values of an [`ArrayStore`].
+#[derive(Clone)]
+pub struct ArrayRuns<'a> {
+    values: &'a [u16],
+}
+
+impl Iterator for ArrayRuns<'_> {
+    type Item = RangeInclusive<u16>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = *self.values.first()?;
+        // The values are strictly ascending, they belong to the run while their
+        // distance to the start is equal to their index
+        let mut len = 1;
+        while self.values.get(len).is_some_and(|&value| usize::from(value - start) == len) {
+            len += 1;
+        }
+        self.values = &self.values[len..];
+        Some(start..=start + (len - 1) as u16)
+    }
+}
+
 #[derive(Debug)]
 pub struct Error {
     index: usize,
@@ -319,27 +447,31 @@ impl Display for Error {
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for Error {}
+impl core::error::Error for Error {}
 
 impl TryFrom<Vec<u16>> for ArrayStore {
     type Error = Error;
 
     fn try_from(value: Vec<u16>) -> Result<Self, Self::Error> {
-        let mut iter = value.iter().enumerate();
-        if let Some((_, mut prev)) = iter.next() {
-            for (i, cur) in iter {
-                match cur.cmp(prev) {
-                    Ordering::Less => return Err(Error { index: i, kind: ErrorKind::OutOfOrder }),
-                    Ordering::Equal => return Err(Error { index: i, kind: ErrorKind::Duplicate }),
-                    Ordering::Greater => (),
-                }
-                prev = cur;
+        validate_sorted(value.iter().copied())?;
+        Ok(ArrayStore { vec: value })
+    }
+}
+
+/// Checks that the values are strictly increasing, as an array store requires them to be.
+pub(crate) fn validate_sorted(values: impl IntoIterator<Item = u16>) -> Result<(), Error> {
+    let mut iter = values.into_iter().enumerate();
+    if let Some((_, mut prev)) = iter.next() {
+        for (i, cur) in iter {
+            match cur.cmp(&prev) {
+                Ordering::Less => return Err(Error { index: i, kind: ErrorKind::OutOfOrder }),
+                Ordering::Equal => return Err(Error { index: i, kind: ErrorKind::Duplicate }),
+                Ordering::Greater => (),
             }
+            prev = cur;
         }
-
-        Ok(ArrayStore { vec: value })
     }
+    Ok(())
 }
 
 impl BitOr<Self> for &ArrayStore {
@@ -348,6 +480,22 @@ impl BitOr<Self> for &ArrayStore {
     fn bitor(self, rhs: Self) -> Self::Output {
         #[allow(clippy::suspicious_arithmetic_impl)]
         let capacity = self.vec.len() + rhs.vec.len();
+
+        // When the bounds of the stores don't overlap, the union is a concatenation
+        let disjoint = if self.max() < rhs.min() {
+            Some((self, rhs))
+        } else if rhs.max() < self.min() {
+            Some((rhs, self))
+        } else {
+            None
+        };
+        if let Some((lower, upper)) = disjoint {
+            let mut vec = Vec::with_capacity(capacity);
+            vec.extend_from_slice(&lower.vec);
+            vec.extend_from_slice(&upper.vec);
+            return ArrayStore { vec };
+        }
+
         let mut visitor = VecWriter::new(capacity);
         #[cfg(feature = "simd")]
         vector::or(self.as_slice(), rhs.as_slice(), &mut visitor);
@@ -384,7 +532,7 @@ impl BitAndAssign<&Self> for ArrayStore {
             let mut i = 0;
             self.retain(|x| {
                 i += rhs.iter().skip(i).position(|y| *y >= x).unwrap_or(rhs.vec.len());
-                rhs.vec.get(i).map_or(false, |y| x == *y)
+                rhs.vec.get(i).is_some_and(|y| x == *y)
             });
         }
     }
@@ -455,6 +603,18 @@ mod tests {
     use super::*;
     use crate::bitmap::store::Store;
 
+    #[test]
+    fn test_validate_unsorted() {
+        use crate::bitmap::container::Container;
+        use crate::bitmap::Invariant;
+        use crate::RoaringBitmap;
+
+        let store = Store::Array(ArrayStore { vec: vec![1, 5, 5] });
+        let rb = RoaringBitmap { containers: vec![Container { key: 3, store }] };
+        let error = rb.validate().unwrap_err();
+        assert_eq!((error.key(), error.invariant()), (3, Invariant::SortedValues { index: 2 }));
+    }
+
     fn into_vec(s: Store) -> Vec<u16> {
         match s {
             Store::Array(vec) => vec.vec,
@@ -523,6 +683,17 @@ mod tests {
         assert!(store.contains_range(0..=5));
         assert!(!store.contains_range(0..=6));
         assert!(store.contains_range(100..=100));
+
+        // Single element ranges
+        assert!(store.contains_range(3..=3));
+        assert!(!store.contains_range(6..=6));
+        assert!(!store.contains_range(u16::MAX..=u16::MAX));
+
+        // Ranges partially overlapping the stored values
+        assert!(!store.contains_range(4..=7));
+        assert!(!store.contains_range(99..=100));
+        assert!(!store.contains_range(100..=101));
+        assert!(!store.contains_range(5..=100));
     }
 
     #[test]
@@ -630,4 +801,65 @@ mod tests {
         store.remove_biggest(2);
         assert_eq!(into_vec(store), vec![1, 2]);
     }
+
+    #[test]
+    fn test_array_capacity_capped_at_limit() {
+        let mut store = ArrayStore::new();
+        for i in 0..ARRAY_LIMIT as u16 {
+            store.insert(i * 3);
+            assert!(store.capacity() <= ARRAY_LIMIT as usize);
+        }
+        assert_eq!(store.capacity(), ARRAY_LIMIT as usize);
+
+        let mut store = ArrayStore::new();
+        for i in 0..ARRAY_LIMIT as u16 {
+            store.push(i);
+            assert!(store.capacity() <= ARRAY_LIMIT as usize);
+        }
+        assert_eq!(store.capacity(), ARRAY_LIMIT as usize);
+
+        let mut store = ArrayStore::new();
+        store.insert_range(0..=2500);
+        store.insert_range(3000..=4094);
+        assert!(store.capacity() <= ARRAY_LIMIT as usize);
+    }
+
+    #[test]
+    fn test_array_insert_past_limit() {
+        let full = ArrayStore::from_vec_unchecked((0..ARRAY_LIMIT as u16 * 2).step_by(2).collect());
+        assert_eq!(full.capacity(), ARRAY_LIMIT as usize);
+
+        // The 4097th value converts the store without growing the array first
+        let mut array = full.clone();
+        array.reserve(1);
+        assert_eq!(array.capacity(), ARRAY_LIMIT as usize);
+
+        let mut store = Store::Array(full.clone());
+        assert!(store.insert(1));
+        assert!(!store.insert(2));
+        assert!(matches!(store, Store::Bitmap(_)));
+        assert_eq!(store.len(), ARRAY_LIMIT + 1);
+
+        let mut store = Store::Array(full);
+        assert!(!store.push(2));
+        assert!(store.push(u16::MAX));
+        assert!(matches!(store, Store::Bitmap(_)));
+        assert_eq!(store.max(), Some(u16::MAX));
+    }
+
+    #[test]
+    fn test_array_bitor_disjoint_bounds() {
+        let low = ArrayStore::from_vec_unchecked(vec![1, 2, 8]);
+        let high = ArrayStore::from_vec_unchecked(vec![9, 100, 500]);
+        let empty = ArrayStore::new();
+
+        assert_eq!((&low | &high).vec, vec![1, 2, 8, 9, 100, 500]);
+        assert_eq!((&high | &low).vec, vec![1, 2, 8, 9, 100, 500]);
+        assert_eq!((&low | &empty).vec, vec![1, 2, 8]);
+        assert_eq!((&empty | &high).vec, vec![9, 100, 500]);
+        assert_eq!((&empty | &empty).vec, Vec::<u16>::new());
+
+        let overlapping = ArrayStore::from_vec_unchecked(vec![8, 9]);
+        assert_eq!((&low | &overlapping).vec, vec![1, 2, 8, 9]);
+    }
 }
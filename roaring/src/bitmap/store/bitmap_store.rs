@@ -5,6 +5,7 @@ use core::mem::size_of;
 use core::ops::{BitAndAssign, BitOrAssign, BitXorAssign, RangeInclusive, SubAssign};
 
 use super::ArrayStore;
+use crate::bitmap::util;
 
 #[cfg(not(feature = "std"))]
 use alloc::boxed::Box;
@@ -13,12 +14,24 @@ use alloc::vec::Vec;
 
 pub const BITMAP_LENGTH: usize = 1024;
 
-#[derive(Clone, Eq, PartialEq)]
+#[derive(Eq, PartialEq)]
+#[cfg_attr(feature = "rkyv", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
 pub struct BitmapStore {
     len: u64,
     bits: Box<[u64; BITMAP_LENGTH]>,
 }
 
+impl Clone for BitmapStore {
+    fn clone(&self) -> Self {
+        BitmapStore { len: self.len, bits: self.bits.clone() }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.len = source.len;
+        self.bits.copy_from_slice(&source.bits[..]);
+    }
+}
+
 impl BitmapStore {
     pub fn new() -> BitmapStore {
         BitmapStore { len: 0, bits: Box::new([0; BITMAP_LENGTH]) }
@@ -43,7 +56,7 @@ impl BitmapStore {
 
     pub fn from_lsb0_bytes_unchecked(bytes: &[u8], byte_offset: usize, bits_set: u64) -> Self {
         const BITMAP_BYTES: usize = BITMAP_LENGTH * size_of::<u64>();
-        assert!(byte_offset.checked_add(bytes.len()).map_or(false, |sum| sum <= BITMAP_BYTES));
+        assert!(byte_offset.checked_add(bytes.len()).is_some_and(|sum| sum <= BITMAP_BYTES));
 
         // If we know we're writing the full bitmap, we can avoid the initial memset to 0
         let mut bits = if bytes.len() == BITMAP_BYTES {
@@ -71,7 +84,7 @@ impl BitmapStore {
         if !cfg!(target_endian = "little") {
             // Convert all words we touched (even partially) to little-endian
             let start_word = byte_offset / size_of::<u64>();
-            let end_word = (byte_offset + bytes.len() + (size_of::<u64>() - 1)) / size_of::<u64>();
+            let end_word = (byte_offset + bytes.len()).div_ceil(size_of::<u64>());
 
             // The 0th byte is the least significant byte, so we've written the bytes in little-endian
             for word in &mut bits[start_word..end_word] {
@@ -109,6 +122,10 @@ impl BitmapStore {
         inserted != 0
     }
 
+    pub fn insert_and_rank(&mut self, index: u16) -> (bool, u64) {
+        (self.insert(index), self.rank(index))
+    }
+
     pub fn insert_range(&mut self, range: RangeInclusive<u16>) -> u64 {
         let start = *range.start();
         let end = *range.end();
@@ -230,10 +247,62 @@ impl BitmapStore {
         removed
     }
 
+    /// Flips every value of the range, returns the number of added values
+    /// minus the number of removed values.
+    pub fn flip_range(&mut self, range: RangeInclusive<u16>) -> i64 {
+        let start = *range.start();
+        let end = *range.end();
+
+        let (start_key, start_bit) = (key(start), bit(start));
+        let (end_key, end_bit) = (key(end), bit(end));
+
+        let old_len = self.len;
+        for i in start_key..=end_key {
+            let mut mask = u64::MAX;
+            if i == start_key {
+                mask &= u64::MAX << start_bit;
+            }
+            if i == end_key {
+                mask &= u64::MAX >> (63 - end_bit);
+            }
+            let existed = u64::from((self.bits[i] & mask).count_ones());
+            let inserted = u64::from(mask.count_ones()) - existed;
+            self.bits[i] ^= mask;
+            self.len = self.len + inserted - existed;
+        }
+
+        self.len as i64 - old_len as i64
+    }
+
     pub fn contains(&self, index: u16) -> bool {
         self.bits[key(index)] & (1 << bit(index)) != 0
     }
 
+    /// Pushes, for each of the values, whether its low 16 bits are in the store.
+    pub fn contains_batch(&self, values: &[u32], bools: &mut Vec<bool>) {
+        #[cfg(feature = "simd")]
+        {
+            use core::simd::cmp::SimdPartialEq;
+            use core::simd::{u64x8, usizex8};
+
+            // Gather the words of 8 values at once and test their bits in parallel
+            let mut chunks = values.chunks_exact(8);
+            for chunk in &mut chunks {
+                let indexes: [u16; 8] = core::array::from_fn(|i| chunk[i] as u16);
+                let keys = usizex8::from_array(indexes.map(key));
+                let bits = u64x8::from_array(indexes.map(|index| bit(index) as u64));
+                let words = u64x8::gather_or_default(&self.bits[..], keys);
+                let set = ((words >> bits) & u64x8::splat(1)).simd_ne(u64x8::splat(0));
+                bools.extend(set.to_array());
+            }
+            bools.extend(chunks.remainder().iter().map(|&value| self.contains(value as u16)));
+        }
+        #[cfg(not(feature = "simd"))]
+        {
+            bools.extend(values.iter().map(|&value| self.contains(value as u16)));
+        }
+    }
+
     pub fn contains_range(&self, range: RangeInclusive<u16>) -> bool {
         let start = *range.start();
         let end = *range.end();
@@ -332,6 +401,21 @@ impl BitmapStore {
         None
     }
 
+    /// Returns the smallest value greater than or equal to `index`, which may be 65536.
+    pub fn next_from(&self, index: usize) -> Option<u16> {
+        let mut key = index / 64;
+        let mut word = *self.bits.get(key)? & (!0 << (index % 64));
+        while word == 0 {
+            key += 1;
+            word = *self.bits.get(key)?;
+        }
+        Some((64 * key + word.trailing_zeros() as usize) as u16)
+    }
+
+    pub fn runs(&self) -> BitmapRuns<'_> {
+        BitmapRuns { bits: &self.bits, key: 0, word: self.bits[0] }
+    }
+
     pub fn intersection_len_bitmap(&self, other: &BitmapStore) -> u64 {
         self.bits.iter().zip(other.bits.iter()).map(|(&a, &b)| (a & b).count_ones() as u64).sum()
     }
@@ -356,6 +440,29 @@ impl BitmapStore {
         BitmapIter::new(self.bits)
     }
 
+    /// Unions the little-endian words of a serialized bitmap container into this store.
+    ///
+    /// Fails if the number of bits set in the serialized words is not `len`,
+    /// the union is applied nonetheless and the store stays valid.
+    #[cfg(feature = "std")]
+    pub(crate) fn union_with_le_bytes(&mut self, len: u64, bytes: &[u8]) -> Result<(), Error> {
+        debug_assert_eq!(bytes.len(), BITMAP_LENGTH * size_of::<u64>());
+        let mut actual_len = 0;
+        self.len = 0;
+        for (word, chunk) in self.bits.iter_mut().zip(bytes.chunks_exact(size_of::<u64>())) {
+            let other = u64::from_le_bytes(chunk.try_into().unwrap());
+            actual_len += u64::from(other.count_ones());
+            *word |= other;
+            self.len += u64::from(word.count_ones());
+        }
+
+        if len != actual_len {
+            Err(Error { kind: ErrorKind::Cardinality { expected: len, actual: actual_len } })
+        } else {
+            Ok(())
+        }
+    }
+
     pub fn as_array(&self) -> &[u64; BITMAP_LENGTH] {
         &self.bits
     }
@@ -365,6 +472,33 @@ impl BitmapStore {
         self.len = 0;
     }
 
+    /// Keeps only the values yielded by the ascending `values`, clearing the other bits in place.
+    pub fn retain_sorted(&mut self, values: impl IntoIterator<Item = u16>) {
+        // The words before `start` are filtered, the word `masked` keeps the bits of `mask`
+        let mut start = 0;
+        let mut masked = None;
+        let mut mask = 0;
+        for value in values {
+            let key = key(value);
+            if masked != Some(key) {
+                if let Some(masked) = masked {
+                    self.bits[masked] &= mask;
+                    start = masked + 1;
+                }
+                self.bits[start..key.max(start)].fill(0);
+                masked = Some(key);
+                mask = 0;
+            }
+            mask |= 1 << bit(value);
+        }
+        if let Some(masked) = masked {
+            self.bits[masked] &= mask;
+            start = masked + 1;
+        }
+        self.bits[start..].fill(0);
+        self.len = self.bits.iter().map(|word| u64::from(word.count_ones())).sum();
+    }
+
     /// Set N bits that are currently 1 bit from the lower bit to 0.
     pub fn remove_smallest(&mut self, mut clear_bits: u64) {
         if self.len() < clear_bits {
@@ -422,6 +556,17 @@ fn select(mut value: u64, n: u64) -> u64 {
     value.trailing_zeros() as u64
 }
 
+#[cfg(feature = "rkyv")]
+impl ArchivedBitmapStore {
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn contains(&self, index: u16) -> bool {
+        self.bits[key(index)] & (1 << bit(index)) != 0
+    }
+}
+
 impl Default for BitmapStore {
     fn default() -> Self {
         BitmapStore::new()
@@ -448,8 +593,48 @@ impl Display for Error {
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for Error {}
+impl core::error::Error for Error {}
+
+/// An iterator over the maximal runs of set bits of a [`BitmapStore`].
+#[derive(Clone)]
+pub struct BitmapRuns<'a> {
+    bits: &'a [u64; BITMAP_LENGTH],
+    key: usize,
+    // The bits of `bits[key]` that are not yielded yet
+    word: u64,
+}
+
+impl Iterator for BitmapRuns<'_> {
+    type Item = RangeInclusive<u16>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Find the start of the run, the first set bit
+        while self.word == 0 {
+            self.key += 1;
+            self.word = *self.bits.get(self.key)?;
+        }
+        let start = self.key * 64 + self.word.trailing_zeros() as usize;
+
+        // Set the bits below the start so that the run is the trailing ones of the word,
+        // then find its end, the first unset bit
+        self.word |= self.word - 1;
+        while self.word == u64::MAX {
+            self.key += 1;
+            match self.bits.get(self.key) {
+                Some(&word) => self.word = word,
+                None => {
+                    self.word = 0;
+                    return Some(start as u16..=u16::MAX);
+                }
+            }
+        }
+        let end = self.key * 64 + self.word.trailing_ones() as usize - 1;
+
+        // Clear the trailing ones of the run
+        self.word &= self.word + 1;
+        Some(start as u16..=end as u16)
+    }
+}
 
 #[derive(Clone)]
 pub struct BitmapIter<B: Borrow<[u64; BITMAP_LENGTH]>> {
@@ -488,6 +673,9 @@ impl<B: Borrow<[u64; BITMAP_LENGTH]>> BitmapIter<B> {
                 } else if cmp == Ordering::Equal {
                     self.value_back
                 } else {
+                    // new_key is past the back of the iterator, it is exhausted
+                    self.key = self.key_back;
+                    self.value = 0;
                     self.value_back = 0;
                     return;
                 }
@@ -500,6 +688,49 @@ impl<B: Borrow<[u64; BITMAP_LENGTH]>> BitmapIter<B> {
         self.value = value & !low_bits;
     }
 
+    /// Returns the value `next` would return, without consuming it.
+    pub(crate) fn peek(&self) -> Option<u16> {
+        if self.value != 0 {
+            return Some(64 * self.key + self.value.trailing_zeros() as u16);
+        }
+        if self.key >= self.key_back {
+            return None;
+        }
+        let bits = self.bits.borrow();
+        for key in self.key + 1..self.key_back {
+            let value = bits[key as usize];
+            if value != 0 {
+                return Some(64 * key + value.trailing_zeros() as u16);
+            }
+        }
+        match self.value_back {
+            0 => None,
+            value => Some(64 * self.key_back + value.trailing_zeros() as u16),
+        }
+    }
+
+    /// Returns the value `next_back` would return, without consuming it.
+    pub(crate) fn peek_back(&self) -> Option<u16> {
+        let value = if self.key_back <= self.key { self.value } else { self.value_back };
+        if value != 0 {
+            return Some(64 * self.key_back + 63 - value.leading_zeros() as u16);
+        }
+        if self.key_back <= self.key {
+            return None;
+        }
+        let bits = self.bits.borrow();
+        for key in (self.key + 1..self.key_back).rev() {
+            let value = bits[key as usize];
+            if value != 0 {
+                return Some(64 * key + 63 - value.leading_zeros() as u16);
+            }
+        }
+        match self.value {
+            0 => None,
+            value => Some(64 * self.key + 63 - value.leading_zeros() as u16),
+        }
+    }
+
     /// Advance the back of iterator to the first value less than or equal to `n`.
     pub(crate) fn advance_back_to(&mut self, index: u16) {
         let new_key = key(index) as u16;
@@ -531,6 +762,39 @@ impl<B: Borrow<[u64; BITMAP_LENGTH]>> BitmapIter<B> {
         self.key_back = new_key;
         *dst = value & low_bits;
     }
+
+    /// Pushes up to `max` values to `buf`, joined with the `high` bits of the container,
+    /// and returns the number of values pushed. Full words are pushed as a whole range.
+    pub(crate) fn fill_buffer(&mut self, high: u16, buf: &mut Vec<u32>, max: usize) -> usize {
+        let mut pushed = 0;
+        while pushed < max {
+            if self.value == 0 {
+                if self.key >= self.key_back {
+                    break;
+                }
+                self.key += 1;
+                self.value = if self.key == self.key_back {
+                    self.value_back
+                } else {
+                    self.bits.borrow()[self.key as usize]
+                };
+                continue;
+            }
+            let offset = util::join(high, 64 * self.key);
+            if self.value == u64::MAX && max - pushed >= 64 {
+                buf.extend(offset..=offset + 63);
+                self.value = 0;
+                pushed += 64;
+            } else {
+                while self.value != 0 && pushed < max {
+                    buf.push(offset + self.value.trailing_zeros());
+                    self.value &= self.value - 1;
+                    pushed += 1;
+                }
+            }
+        }
+        pushed
+    }
 }
 
 impl<B: Borrow<[u64; BITMAP_LENGTH]>> Iterator for BitmapIter<B> {
@@ -578,6 +842,32 @@ impl<B: Borrow<[u64; BITMAP_LENGTH]>> Iterator for BitmapIter<B> {
     {
         self.len()
     }
+
+    fn nth(&mut self, mut n: usize) -> Option<Self::Item> {
+        // Skip the whole words that contain less than `n` values
+        loop {
+            let ones = self.value.count_ones() as usize;
+            if n < ones {
+                break;
+            }
+            n -= ones;
+            if self.key >= self.key_back {
+                self.value = 0;
+                return None;
+            }
+            self.key += 1;
+            self.value = if self.key == self.key_back {
+                self.value_back
+            } else {
+                self.bits.borrow()[self.key as usize]
+            };
+        }
+        // Clear the `n` lowest bits of the word containing the value
+        for _ in 0..n {
+            self.value &= self.value - 1;
+        }
+        self.next()
+    }
 }
 
 impl<B: Borrow<[u64; BITMAP_LENGTH]>> DoubleEndedIterator for BitmapIter<B> {
@@ -600,6 +890,30 @@ impl<B: Borrow<[u64; BITMAP_LENGTH]>> DoubleEndedIterator for BitmapIter<B> {
             return Some(64 * self.key_back + index);
         }
     }
+
+    fn nth_back(&mut self, mut n: usize) -> Option<Self::Item> {
+        // Skip the whole words that contain less than `n` values
+        loop {
+            let value = if self.key_back <= self.key { self.value } else { self.value_back };
+            let ones = value.count_ones() as usize;
+            if n < ones {
+                break;
+            }
+            n -= ones;
+            if self.key_back <= self.key {
+                self.value = 0;
+                return None;
+            }
+            self.key_back -= 1;
+            self.value_back = self.bits.borrow()[self.key_back as usize];
+        }
+        // Clear the `n` highest bits of the word containing the value
+        let value = if self.key_back <= self.key { &mut self.value } else { &mut self.value_back };
+        for _ in 0..n {
+            *value &= !(1 << (63 - value.leading_zeros()));
+        }
+        self.next_back()
+    }
 }
 
 impl<B: Borrow<[u64; BITMAP_LENGTH]>> ExactSizeIterator for BitmapIter<B> {}
@@ -691,6 +1005,49 @@ impl BitXorAssign<&ArrayStore> for BitmapStore {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_validate_cardinality() {
+        use crate::bitmap::container::Container;
+        use crate::bitmap::store::Store;
+        use crate::bitmap::Invariant;
+        use crate::RoaringBitmap;
+
+        let store = Store::Bitmap(BitmapStore { len: 5000, bits: Box::new([1; BITMAP_LENGTH]) });
+        let rb = RoaringBitmap { containers: vec![Container { key: 4, store }] };
+        let error = rb.validate().unwrap_err();
+        let invariant = Invariant::Cardinality { expected: 5000, actual: 1024 };
+        assert_eq!((error.key(), error.invariant()), (4, invariant));
+    }
+
+    #[test]
+    fn test_bitmap_retain_sorted() {
+        let mut store = BitmapStore::full();
+        store.retain_sorted([0, 1, 1, 63, 64, 1000, 65535]);
+        assert_eq!(store.len(), 6);
+        assert_eq!(store.iter().collect::<Vec<u16>>(), [0, 1, 63, 64, 1000, 65535]);
+
+        store.retain_sorted([1, 2, 1000]);
+        assert_eq!(store.iter().collect::<Vec<u16>>(), [1, 1000]);
+
+        store.retain_sorted([]);
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_bitmap_next_from() {
+        let mut store = BitmapStore::new();
+        for index in [3, 64, 1000, 65535] {
+            store.insert(index);
+        }
+        assert_eq!(store.next_from(0), Some(3));
+        assert_eq!(store.next_from(3), Some(3));
+        assert_eq!(store.next_from(4), Some(64));
+        assert_eq!(store.next_from(65), Some(1000));
+        assert_eq!(store.next_from(1001), Some(65535));
+        assert_eq!(store.next_from(65536), None);
+        assert_eq!(BitmapStore::new().next_from(0), None);
+    }
+
     #[test]
     fn test_bitmap_remove_smallest() {
         let mut store = BitmapStore::new();
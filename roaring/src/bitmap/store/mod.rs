@@ -11,20 +11,44 @@ use core::slice;
 pub use self::bitmap_store::BITMAP_LENGTH;
 use self::Store::{Array, Bitmap};
 
-pub use self::array_store::ArrayStore;
-pub use self::bitmap_store::{BitmapIter, BitmapStore};
+#[cfg(feature = "std")]
+pub(crate) use self::array_store::validate_sorted;
+pub use self::array_store::{ArrayRuns, ArrayStore};
+pub use self::bitmap_store::{BitmapIter, BitmapRuns, BitmapStore};
 
 use crate::bitmap::container::ARRAY_LIMIT;
+use crate::bitmap::util;
+use crate::bitmap::ContainerKind;
 
 #[cfg(not(feature = "std"))]
 use alloc::boxed::Box;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 
-#[derive(Clone)]
+#[cfg_attr(feature = "rkyv", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
 pub enum Store {
     Array(ArrayStore),
     Bitmap(BitmapStore),
 }
 
+impl Clone for Store {
+    fn clone(&self) -> Self {
+        match self {
+            Array(vec) => Array(vec.clone()),
+            Bitmap(bits) => Bitmap(bits.clone()),
+        }
+    }
+
+    /// Reuses the allocation of `self` when both stores are of the same kind.
+    fn clone_from(&mut self, source: &Self) {
+        match (self, source) {
+            (Array(vec), Array(source)) => vec.clone_from(source),
+            (Bitmap(bits), Bitmap(source)) => bits.clone_from(source),
+            (this, source) => *this = source.clone(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub enum Iter<'a> {
     Array(slice::Iter<'a, u16>),
@@ -33,6 +57,24 @@ pub enum Iter<'a> {
     BitmapOwned(BitmapIter<Box<[u64; BITMAP_LENGTH]>>),
 }
 
+/// An iterator over the maximal runs of a [`Store`], in ascending order.
+#[derive(Clone)]
+pub enum Runs<'a> {
+    Array(ArrayRuns<'a>),
+    Bitmap(BitmapRuns<'a>),
+}
+
+impl Iterator for Runs<'_> {
+    type Item = RangeInclusive<u16>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Runs::Array(runs) => runs.next(),
+            Runs::Bitmap(runs) => runs.next(),
+        }
+    }
+}
+
 impl Store {
     pub fn new() -> Store {
         Store::Array(ArrayStore::new())
@@ -72,21 +114,58 @@ impl Store {
             return None;
         }
 
-        Some(if bits_set < ARRAY_LIMIT {
+        Some(if bits_set <= ARRAY_LIMIT {
             Array(ArrayStore::from_lsb0_bytes(bytes, byte_offset, bits_set))
         } else {
             Bitmap(BitmapStore::from_lsb0_bytes_unchecked(bytes, byte_offset, bits_set))
         })
     }
 
+    pub fn from_words(words: &[u64]) -> Option<Self> {
+        assert!(words.len() <= BITMAP_LENGTH);
+
+        let bits_set = words.iter().map(|word| u64::from(word.count_ones())).sum();
+        if bits_set == 0 {
+            return None;
+        }
+
+        Some(if bits_set <= ARRAY_LIMIT {
+            Array(ArrayStore::from_words(words, bits_set))
+        } else {
+            let mut bits = Box::new([0u64; BITMAP_LENGTH]);
+            bits[..words.len()].copy_from_slice(words);
+            Bitmap(BitmapStore::from_unchecked(bits_set, bits))
+        })
+    }
+
     #[inline]
     pub fn insert(&mut self, index: u16) -> bool {
         match self {
+            // A full array becomes a bitmap before the value is added, instead of growing
+            Array(vec) if vec.len() == ARRAY_LIMIT && !vec.contains(index) => {
+                let mut bits = vec.to_bitmap_store();
+                bits.insert(index);
+                *self = Bitmap(bits);
+                true
+            }
             Array(vec) => vec.insert(index),
             Bitmap(bits) => bits.insert(index),
         }
     }
 
+    pub fn insert_and_rank(&mut self, index: u16) -> (bool, u64) {
+        match self {
+            Array(vec) if vec.len() == ARRAY_LIMIT && !vec.contains(index) => {
+                let mut bits = vec.to_bitmap_store();
+                let result = bits.insert_and_rank(index);
+                *self = Bitmap(bits);
+                result
+            }
+            Array(vec) => vec.insert_and_rank(index),
+            Bitmap(bits) => bits.insert_and_rank(index),
+        }
+    }
+
     pub fn insert_range(&mut self, range: RangeInclusive<u16>) -> u64 {
         // A Range is defined as being of size 0 if start >= end.
         if range.is_empty() {
@@ -104,6 +183,12 @@ impl Store {
     /// Returns whether `index` was effectively pushed.
     pub fn push(&mut self, index: u16) -> bool {
         match self {
+            Array(vec) if vec.len() == ARRAY_LIMIT && vec.max() < Some(index) => {
+                let mut bits = vec.to_bitmap_store();
+                bits.push_unchecked(index);
+                *self = Bitmap(bits);
+                true
+            }
             Array(vec) => vec.push(index),
             Bitmap(bits) => bits.push(index),
         }
@@ -118,6 +203,11 @@ impl Store {
     /// If debug_assertions enabled and index is > self.max()
     pub(crate) fn push_unchecked(&mut self, index: u16) {
         match self {
+            Array(vec) if vec.len() == ARRAY_LIMIT => {
+                let mut bits = vec.to_bitmap_store();
+                bits.push_unchecked(index);
+                *self = Bitmap(bits);
+            }
             Array(vec) => vec.push_unchecked(index),
             Bitmap(bits) => bits.push_unchecked(index),
         }
@@ -141,6 +231,17 @@ impl Store {
         }
     }
 
+    pub fn flip_range(&mut self, range: RangeInclusive<u16>) -> i64 {
+        if range.is_empty() {
+            return 0;
+        }
+
+        match self {
+            Array(vec) => vec.flip_range(range),
+            Bitmap(bits) => bits.flip_range(range),
+        }
+    }
+
     pub fn remove_smallest(&mut self, index: u64) {
         match self {
             Array(vec) => vec.remove_smallest(index),
@@ -162,6 +263,14 @@ impl Store {
         }
     }
 
+    /// Pushes, for each of the values, whether its low 16 bits are in the store.
+    pub fn contains_batch(&self, values: &[u32], bools: &mut Vec<bool>) {
+        match self {
+            Array(vec) => bools.extend(values.iter().map(|&value| vec.contains(value as u16))),
+            Bitmap(bits) => bits.contains_batch(values, bools),
+        }
+    }
+
     pub fn contains_range(&self, range: RangeInclusive<u16>) -> bool {
         match self {
             Array(vec) => vec.contains_range(range),
@@ -215,6 +324,13 @@ impl Store {
         }
     }
 
+    pub fn kind(&self) -> ContainerKind {
+        match self {
+            Array(_) => ContainerKind::Array,
+            Bitmap(_) => ContainerKind::Bitset,
+        }
+    }
+
     pub fn min(&self) -> Option<u16> {
         match self {
             Array(vec) => vec.min(),
@@ -244,6 +360,41 @@ impl Store {
         }
     }
 
+    pub fn runs(&self) -> Runs<'_> {
+        match self {
+            Array(vec) => Runs::Array(vec.runs()),
+            Bitmap(bits) => Runs::Bitmap(bits.runs()),
+        }
+    }
+
+    /// Returns the cursor of the smallest value greater than or equal to `index`, see
+    /// [`Store::next_at`].
+    pub(crate) fn cursor_at(&self, index: u16) -> usize {
+        match self {
+            Array(vec) => vec.as_slice().partition_point(|&value| value < index),
+            Bitmap(_) => index as usize,
+        }
+    }
+
+    /// Returns the value at the `cursor` and moves the cursor past it.
+    ///
+    /// The cursor is an index in the values of an array store and a value of a bitmap store,
+    /// it lets a caller iterate without keeping the store borrowed.
+    pub(crate) fn next_at(&self, cursor: &mut usize) -> Option<u16> {
+        match self {
+            Array(vec) => {
+                let value = *vec.as_slice().get(*cursor)?;
+                *cursor += 1;
+                Some(value)
+            }
+            Bitmap(bits) => {
+                let value = bits.next_from(*cursor)?;
+                *cursor = value as usize + 1;
+                Some(value)
+            }
+        }
+    }
+
     pub(crate) fn to_bitmap(&self) -> Store {
         match self {
             Array(arr) => Bitmap(arr.to_bitmap_store()),
@@ -252,6 +403,23 @@ impl Store {
     }
 }
 
+#[cfg(feature = "rkyv")]
+impl ArchivedStore {
+    pub fn len(&self) -> u64 {
+        match self {
+            ArchivedStore::Array(vec) => vec.len(),
+            ArchivedStore::Bitmap(bits) => bits.len(),
+        }
+    }
+
+    pub fn contains(&self, index: u16) -> bool {
+        match self {
+            ArchivedStore::Array(vec) => vec.contains(index),
+            ArchivedStore::Bitmap(bits) => bits.contains(index),
+        }
+    }
+}
+
 impl Default for Store {
     fn default() -> Self {
         Store::new()
@@ -398,6 +566,19 @@ impl BitAndAssign<&Store> for Store {
     }
 }
 
+impl Store {
+    /// Intersects in place with `rhs`, like `&=`, but never clones `self` even when it is the
+    /// largest operand, arrays are filtered in their existing allocation.
+    pub fn intersect_with(&mut self, rhs: &Store) {
+        match (self, rhs) {
+            (Array(vec1), Array(vec2)) => BitAndAssign::bitand_assign(vec1, vec2),
+            (Array(vec1), Bitmap(bits2)) => BitAndAssign::bitand_assign(vec1, bits2),
+            (Bitmap(bits1), Bitmap(bits2)) => BitAndAssign::bitand_assign(bits1, bits2),
+            (this @ Bitmap(..), Array(..)) => BitAndAssign::bitand_assign(this, rhs),
+        }
+    }
+}
+
 impl Sub<&Store> for &Store {
     type Output = Store;
 
@@ -530,6 +711,24 @@ impl PartialEq for Store {
 }
 
 impl Iter<'_> {
+    pub(crate) fn peek(&self) -> Option<u16> {
+        match self {
+            Iter::Array(inner) => inner.as_slice().first().copied(),
+            Iter::Vec(inner) => inner.as_slice().first().copied(),
+            Iter::BitmapBorrowed(inner) => inner.peek(),
+            Iter::BitmapOwned(inner) => inner.peek(),
+        }
+    }
+
+    pub(crate) fn peek_back(&self) -> Option<u16> {
+        match self {
+            Iter::Array(inner) => inner.as_slice().last().copied(),
+            Iter::Vec(inner) => inner.as_slice().last().copied(),
+            Iter::BitmapBorrowed(inner) => inner.peek_back(),
+            Iter::BitmapOwned(inner) => inner.peek_back(),
+        }
+    }
+
     /// Advance the iterator to the first value greater than or equal to `n`.
     pub(crate) fn advance_to(&mut self, n: u16) {
         match self {
@@ -572,6 +771,31 @@ impl Iter<'_> {
             Iter::BitmapOwned(inner) => inner.advance_back_to(n),
         }
     }
+
+    /// Pushes up to `max` values to `buf`, joined with the `high` bits of the container,
+    /// and returns the number of values pushed.
+    pub(crate) fn fill_buffer(&mut self, high: u16, buf: &mut Vec<u32>, max: usize) -> usize {
+        match self {
+            Iter::Array(inner) => {
+                let n = max.min(inner.len());
+                buf.extend(inner.as_slice()[..n].iter().map(|&low| util::join(high, low)));
+                if let Some(nth) = n.checked_sub(1) {
+                    inner.nth(nth);
+                }
+                n
+            }
+            Iter::Vec(inner) => {
+                let n = max.min(inner.len());
+                buf.extend(inner.as_slice()[..n].iter().map(|&low| util::join(high, low)));
+                if let Some(nth) = n.checked_sub(1) {
+                    inner.nth(nth);
+                }
+                n
+            }
+            Iter::BitmapBorrowed(inner) => inner.fill_buffer(high, buf, max),
+            Iter::BitmapOwned(inner) => inner.fill_buffer(high, buf, max),
+        }
+    }
 }
 
 impl Iterator for Iter<'_> {
@@ -626,6 +850,15 @@ impl DoubleEndedIterator for Iter<'_> {
             Iter::BitmapOwned(inner) => inner.next_back(),
         }
     }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        match self {
+            Iter::Array(inner) => inner.nth_back(n).copied(),
+            Iter::Vec(inner) => inner.nth_back(n),
+            Iter::BitmapBorrowed(inner) => inner.nth_back(n),
+            Iter::BitmapOwned(inner) => inner.nth_back(n),
+        }
+    }
 }
 
 impl ExactSizeIterator for Iter<'_> {}
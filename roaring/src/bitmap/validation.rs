@@ -0,0 +1,183 @@
+use core::fmt;
+
+use crate::bitmap::container::ARRAY_LIMIT;
+use crate::RoaringBitmap;
+
+use super::store::Store;
+use super::ContainerKind;
+
+/// An error type that is returned when a [`RoaringBitmap`] breaks one of its invariants,
+/// see [`RoaringBitmap::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ValidationError {
+    key: u16,
+    invariant: Invariant,
+}
+
+impl ValidationError {
+    /// Returns the key of the first container breaking an invariant.
+    pub fn key(&self) -> u16 {
+        self.key
+    }
+
+    /// Returns the invariant broken by the container.
+    pub fn invariant(&self) -> Invariant {
+        self.invariant
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "container {}: {}", self.key, self.invariant)
+    }
+}
+
+impl core::error::Error for ValidationError {}
+
+/// An invariant of a [`RoaringBitmap`] container, see [`ValidationError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Invariant {
+    /// The container key is not greater than the key of the previous container.
+    SortedKeys,
+    /// The container is not empty, empty containers are removed.
+    NonEmpty,
+    /// The values of an array container are strictly ascending.
+    SortedValues {
+        /// The position of the first value breaking the order.
+        index: usize,
+    },
+    /// The cached cardinality of a bitset container is its number of set bits.
+    Cardinality {
+        /// The cached cardinality.
+        expected: u64,
+        /// The number of set bits.
+        actual: u64,
+    },
+    /// Arrays hold at most 4096 values and bitsets more than 4096.
+    StoreKind {
+        /// The kind of the container.
+        kind: ContainerKind,
+        /// The number of values in the container.
+        len: u64,
+    },
+}
+
+impl fmt::Display for Invariant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Invariant::SortedKeys => f.write_str("keys are not strictly ascending"),
+            Invariant::NonEmpty => f.write_str("container is empty"),
+            Invariant::SortedValues { index } => {
+                write!(f, "array values are not strictly ascending at index {index}")
+            }
+            Invariant::Cardinality { expected, actual } => {
+                write!(f, "expected cardinality was {expected} but was {actual}")
+            }
+            Invariant::StoreKind { kind, len } => {
+                write!(f, "{len} values must not be stored in a {kind:?} container")
+            }
+        }
+    }
+}
+
+impl RoaringBitmap {
+    /// Checks the invariants of the bitmap, returning the first one that is broken.
+    ///
+    /// The containers must be sorted by key and not empty, the array values must be
+    /// strictly ascending, the cardinality of the bitsets must match their bits, and
+    /// each container must be stored with the kind its cardinality requires.
+    ///
+    /// The operations of this crate maintain these invariants, this is a debugging
+    /// tool to check for a corruption.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (1..4).chain(100_000..200_000).collect();
+    /// assert_eq!(rb.validate(), Ok(()));
+    /// ```
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut previous_key = None;
+        for container in &self.containers {
+            let key = container.key;
+            let error = |invariant| ValidationError { key, invariant };
+
+            if previous_key.is_some_and(|previous| previous >= key) {
+                return Err(error(Invariant::SortedKeys));
+            }
+            previous_key = Some(key);
+
+            if container.is_empty() {
+                return Err(error(Invariant::NonEmpty));
+            }
+
+            match &container.store {
+                Store::Array(vec) => {
+                    let values = vec.as_slice();
+                    if let Some(index) = values.windows(2).position(|pair| pair[0] >= pair[1]) {
+                        return Err(error(Invariant::SortedValues { index: index + 1 }));
+                    }
+                }
+                Store::Bitmap(bits) => {
+                    let actual =
+                        bits.as_array().iter().map(|word| u64::from(word.count_ones())).sum();
+                    if bits.len() != actual {
+                        return Err(error(Invariant::Cardinality { expected: bits.len(), actual }));
+                    }
+                }
+            }
+
+            let (kind, len) = (container.store.kind(), container.len());
+            let expected_kind =
+                if len <= ARRAY_LIMIT { ContainerKind::Array } else { ContainerKind::Bitset };
+            if kind != expected_kind {
+                return Err(error(Invariant::StoreKind { kind, len }));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bitmap::container::Container;
+    use crate::bitmap::store::{ArrayStore, Store};
+    use crate::bitmap::ContainerKind;
+    use crate::RoaringBitmap;
+
+    use super::{Invariant, ValidationError};
+
+    #[cfg(not(feature = "std"))]
+    use alloc::{vec, vec::Vec};
+
+    fn array(key: u16, values: Vec<u16>) -> Container {
+        Container { key, store: Store::Array(ArrayStore::from_vec_unchecked(values)) }
+    }
+
+    fn error(key: u16, invariant: Invariant) -> Result<(), ValidationError> {
+        Err(ValidationError { key, invariant })
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut rb = RoaringBitmap::from([1, 2, 1 << 16]);
+        rb.insert_range(2 << 16..3 << 16);
+        assert_eq!(rb.validate(), Ok(()));
+        assert_eq!(RoaringBitmap::new().validate(), Ok(()));
+
+        let rb = RoaringBitmap { containers: vec![array(2, vec![1]), array(2, vec![2])] };
+        assert_eq!(rb.validate(), error(2, Invariant::SortedKeys));
+
+        let rb = RoaringBitmap { containers: vec![array(1, vec![1]), array(2, vec![])] };
+        assert_eq!(rb.validate(), error(2, Invariant::NonEmpty));
+
+        let mut rb = RoaringBitmap::from([1, 2, 3]);
+        rb.containers[0].store = rb.containers[0].store.to_bitmap();
+        let invariant = Invariant::StoreKind { kind: ContainerKind::Bitset, len: 3 };
+        assert_eq!(rb.validate(), error(0, invariant));
+    }
+}
@@ -28,9 +28,16 @@ pub mod bitmap;
 /// A compressed bitmap with u64 values.  Implemented as a `BTreeMap` of `RoaringBitmap`s.
 pub mod treemap;
 
+mod macros;
+
 pub use bitmap::RoaringBitmap;
 pub use treemap::RoaringTreemap;
 
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::macros::InsertInto;
+}
+
 /// An error type that is returned when an iterator isn't sorted.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct NonSortedIntegers {
@@ -50,8 +57,35 @@ impl fmt::Display for NonSortedIntegers {
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for NonSortedIntegers {}
+impl core::error::Error for NonSortedIntegers {}
+
+/// An error type that is returned when a byte slice doesn't fit in a [`RoaringBitmap`]
+/// at the requested offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LsbBytesError {
+    offset: u32,
+    len: usize,
+}
+
+impl LsbBytesError {
+    /// Returns the offset, in bits, at which the bytes were to be applied.
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Returns the length, in bytes, of the rejected byte slice.
+    pub fn bytes_len(&self) -> usize {
+        self.len
+    }
+}
+
+impl fmt::Display for LsbBytesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} bytes at bit offset {} do not fit in 2^32 bits", self.len, self.offset)
+    }
+}
+
+impl core::error::Error for LsbBytesError {}
 
 /// A [`Iterator::collect`] blanket implementation that provides extra methods for [`RoaringBitmap`]
 /// and [`RoaringTreemap`].
@@ -59,6 +93,10 @@ impl std::error::Error for NonSortedIntegers {}
 /// When merging multiple bitmap with the same operation it's usually faster to call the
 /// method in this trait than to write your own for loop and merging the bitmaps yourself.
 ///
+/// Every operation returns an empty bitmap when the iterator is empty, this includes the
+/// `intersection` which does not return the full universe. With a single element, every
+/// operation returns that element.
+///
 /// # Examples
 /// ```
 /// use roaring::{MultiOps, RoaringBitmap};
@@ -85,11 +123,32 @@ pub trait MultiOps<T>: IntoIterator<Item = T> {
     fn union(self) -> Self::Output;
 
     /// The `intersection` between all elements.
+    ///
+    /// The intersection of no elements is the empty set.
     fn intersection(self) -> Self::Output;
 
     /// The `difference` between all elements.
     fn difference(self) -> Self::Output;
 
     /// The `symmetric difference` between all elements.
+    ///
+    /// With more than two elements, this is the set of the values appearing in an odd
+    /// number of elements. The parity of each value is tracked in a single merge of the
+    /// containers by key, the containers sharing a key are xored in a bitset in place.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{MultiOps, RoaringBitmap};
+    ///
+    /// let bitmaps = [
+    ///     RoaringBitmap::from([1, 2, 3]),
+    ///     RoaringBitmap::from([2, 3, 4]),
+    ///     RoaringBitmap::from([3, 4, 5]),
+    /// ];
+    ///
+    /// // 1, 3 and 5 appear in an odd number of bitmaps
+    /// assert_eq!(bitmaps.symmetric_difference(), RoaringBitmap::from([1, 3, 5]));
+    /// ```
     fn symmetric_difference(self) -> Self::Output;
 }
@@ -0,0 +1,110 @@
+use core::ops::{Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive};
+
+use crate::{RoaringBitmap, RoaringTreemap};
+
+/// Creates a [`RoaringBitmap`] containing the given values and ranges.
+///
+/// Values are added with [`RoaringBitmap::insert`] and ranges with
+/// [`RoaringBitmap::insert_range`], a trailing comma is accepted.
+///
+/// # Examples
+///
+/// ```rust
+/// use roaring::{roaring, RoaringBitmap};
+///
+/// let rb = roaring![1, 2, 3, 10..=20, 100..200];
+///
+/// let mut expected = RoaringBitmap::new();
+/// expected.insert(1);
+/// expected.insert(2);
+/// expected.insert(3);
+/// expected.insert_range(10..=20);
+/// expected.insert_range(100..200);
+/// assert_eq!(rb, expected);
+///
+/// assert_eq!(roaring![], RoaringBitmap::new());
+/// ```
+#[macro_export]
+macro_rules! roaring {
+    ($($item:expr),* $(,)?) => {{
+        let mut bitmap = $crate::RoaringBitmap::new();
+        $( $crate::__private::InsertInto::insert_into($item, &mut bitmap); )*
+        bitmap
+    }};
+}
+
+/// Creates a [`RoaringTreemap`] containing the given values and ranges.
+///
+/// Values are added with [`RoaringTreemap::insert`] and ranges with
+/// [`RoaringTreemap::insert_range`], a trailing comma is accepted.
+///
+/// # Examples
+///
+/// ```rust
+/// use roaring::{roaring_treemap, RoaringTreemap};
+///
+/// let rt = roaring_treemap![1, u64::MAX, 1 << 40..=(1 << 40) + 10,];
+///
+/// let mut expected = RoaringTreemap::new();
+/// expected.insert(1);
+/// expected.insert(u64::MAX);
+/// expected.insert_range(1 << 40..=(1 << 40) + 10);
+/// assert_eq!(rt, expected);
+/// ```
+#[macro_export]
+macro_rules! roaring_treemap {
+    ($($item:expr),* $(,)?) => {{
+        let mut treemap = $crate::RoaringTreemap::new();
+        $( $crate::__private::InsertInto::insert_into($item, &mut treemap); )*
+        treemap
+    }};
+}
+
+/// A value or a range of values that the construction macros can insert.
+#[doc(hidden)]
+pub trait InsertInto<B> {
+    fn insert_into(self, bitmap: &mut B);
+}
+
+impl InsertInto<RoaringBitmap> for u32 {
+    fn insert_into(self, bitmap: &mut RoaringBitmap) {
+        bitmap.insert(self);
+    }
+}
+
+impl InsertInto<RoaringTreemap> for u64 {
+    fn insert_into(self, treemap: &mut RoaringTreemap) {
+        treemap.insert(self);
+    }
+}
+
+macro_rules! impl_insert_range_into {
+    ($bitmap:ty, $($range:ty),*) => {
+        $(
+            impl InsertInto<$bitmap> for $range {
+                fn insert_into(self, bitmap: &mut $bitmap) {
+                    bitmap.insert_range(self);
+                }
+            }
+        )*
+    };
+}
+
+impl_insert_range_into!(
+    RoaringBitmap,
+    Range<u32>,
+    RangeInclusive<u32>,
+    RangeFrom<u32>,
+    RangeTo<u32>,
+    RangeToInclusive<u32>,
+    RangeFull
+);
+
+impl_insert_range_into!(
+    RoaringTreemap,
+    Range<u64>,
+    RangeInclusive<u64>,
+    RangeFrom<u64>,
+    RangeTo<u64>,
+    RangeToInclusive<u64>
+);
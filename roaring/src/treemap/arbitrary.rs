@@ -10,7 +10,7 @@ mod test {
                 // we’re NEVER supposed to start with a treemap containing empty bitmaps
                 // Since we can’t configure this in arbitrary we’re simply going to ignore the generated empty bitmaps
                 let map = map.into_iter().filter(|(_, v)| !v.is_empty()).collect();
-               RoaringTreemap { map }
+               RoaringTreemap::from_map(map)
            }
         }
     }
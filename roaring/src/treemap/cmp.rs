@@ -104,6 +104,39 @@ impl RoaringTreemap {
     pub fn is_superset(&self, other: &Self) -> bool {
         other.is_subset(self)
     }
+
+    /// Returns `true` if the set holds exactly the same values as the 32-bit bitmap, i.e. its
+    /// only bucket is bucket 0 and it is equal to `other`.
+    ///
+    /// This is intentionally not a `PartialEq<RoaringBitmap>` implementation, which would make
+    /// comparisons between the two types ambiguous to type inference.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringTreemap};
+    ///
+    /// let mut rb1 = RoaringTreemap::new();
+    /// let mut rb2 = RoaringBitmap::new();
+    ///
+    /// assert!(rb1.eq_bitmap(&rb2));
+    ///
+    /// rb1.insert(1);
+    /// rb2.insert(1);
+    ///
+    /// assert!(rb1.eq_bitmap(&rb2));
+    ///
+    /// rb1.insert(1 << 32);
+    ///
+    /// assert!(!rb1.eq_bitmap(&rb2));
+    /// ```
+    pub fn eq_bitmap(&self, other: &RoaringBitmap) -> bool {
+        match self.map.len() {
+            0 => other.is_empty(),
+            1 => self.map.get(&0) == Some(other),
+            _ => false,
+        }
+    }
 }
 
 impl<'a> Iterator for Pairs<'a> {
@@ -5,9 +5,20 @@ use crate::RoaringTreemap;
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 
+/// The maximum number of buckets listed by the alternate (`{:#?}`) representation.
+const MAX_DEBUG_BUCKETS: usize = 16;
+
 impl fmt::Debug for RoaringTreemap {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self.len() < 16 {
+        if f.alternate() {
+            f.debug_struct("RoaringTreemap")
+                .field("len", &self.len())
+                .field("n_buckets", &self.map.len())
+                .field("min", &self.min())
+                .field("max", &self.max())
+                .field("buckets", &DebugBuckets(self))
+                .finish()
+        } else if self.len() < 16 {
             write!(f, "RoaringTreemap<{:?}>", self.iter().collect::<Vec<u64>>())
         } else {
             write!(
@@ -20,3 +31,77 @@ impl fmt::Debug for RoaringTreemap {
         }
     }
 }
+
+/// Formats the buckets of a treemap as a map from their key to their cardinality.
+///
+/// Only the first [`MAX_DEBUG_BUCKETS`] buckets are listed, the rest is elided.
+struct DebugBuckets<'a>(&'a RoaringTreemap);
+
+impl fmt::Debug for DebugBuckets<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut map = f.debug_map();
+        for (key, bitmap) in self.0.map.iter().take(MAX_DEBUG_BUCKETS) {
+            map.entry(key, &bitmap.len());
+        }
+        if self.0.map.len() > MAX_DEBUG_BUCKETS {
+            map.entry(&format_args!(".."), &format_args!(".."));
+        }
+        map.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::RoaringTreemap;
+
+    #[cfg(not(feature = "std"))]
+    use alloc::format;
+
+    #[test]
+    fn debug_small() {
+        let treemap: RoaringTreemap = (1..4).collect();
+        assert_eq!(format!("{:?}", treemap), "RoaringTreemap<[1, 2, 3]>");
+    }
+
+    #[test]
+    fn debug_summary() {
+        let mut treemap: RoaringTreemap = (0..100).collect();
+        treemap.insert(u64::MAX);
+        assert_eq!(
+            format!("{:?}", treemap),
+            "RoaringTreemap<101 values between 0 and 18446744073709551615>"
+        );
+    }
+
+    #[test]
+    fn debug_alternate_buckets() {
+        let mut treemap: RoaringTreemap = (0..1000).collect();
+        treemap.insert(5 << 32);
+        treemap.insert(u64::MAX);
+
+        let expected = "RoaringTreemap {
+    len: 1002,
+    n_buckets: 3,
+    min: Some(
+        0,
+    ),
+    max: Some(
+        18446744073709551615,
+    ),
+    buckets: {
+        0: 1000,
+        5: 1,
+        4294967295: 1,
+    },
+}";
+        assert_eq!(format!("{:#?}", treemap), expected);
+    }
+
+    #[test]
+    fn debug_alternate_elides_buckets() {
+        let treemap: RoaringTreemap = (0..20).map(|i| i << 32).collect();
+        let debug = format!("{:#?}", treemap);
+        assert!(debug.contains("15: 1,\n        ..: ..,\n    },"));
+        assert!(!debug.contains("16: 1,"));
+    }
+}
@@ -20,7 +20,7 @@ impl RoaringTreemap {
     /// let rb = RoaringTreemap::new();
     /// ```
     pub fn new() -> RoaringTreemap {
-        RoaringTreemap { map: BTreeMap::new() }
+        RoaringTreemap { map: BTreeMap::new(), len: 0 }
     }
 
     /// Creates a full `RoaringTreemap`.
@@ -32,7 +32,7 @@ impl RoaringTreemap {
     /// let rb = RoaringTreemap::full();
     /// ```
     pub fn full() -> RoaringTreemap {
-        RoaringTreemap { map: (0..=u32::MAX).zip(iter::repeat(RoaringBitmap::full())).collect() }
+        RoaringTreemap::from_map((0..=u32::MAX).zip(iter::repeat(RoaringBitmap::full())).collect())
     }
 
     /// Adds a value to the set. Returns `true` if the value was not already present in the set.
@@ -49,7 +49,9 @@ impl RoaringTreemap {
     /// ```
     pub fn insert(&mut self, value: u64) -> bool {
         let (hi, lo) = util::split(value);
-        self.map.entry(hi).or_default().insert(lo)
+        let inserted = self.map.entry(hi).or_default().insert(lo);
+        self.len += u64::from(inserted);
+        inserted
     }
 
     /// Inserts a range of values.
@@ -103,6 +105,7 @@ impl RoaringTreemap {
             };
         }
 
+        self.len += counter;
         counter
     }
 
@@ -125,7 +128,9 @@ impl RoaringTreemap {
     /// ```
     pub fn push(&mut self, value: u64) -> bool {
         let (hi, lo) = util::split(value);
-        self.map.entry(hi).or_default().push(lo)
+        let pushed = self.map.entry(hi).or_default().push(lo);
+        self.len += u64::from(pushed);
+        pushed
     }
 
     /// Pushes `value` in the treemap only if it is greater than the current maximum value.
@@ -149,6 +154,7 @@ impl RoaringTreemap {
                 self.map.insert(hi, rb);
             }
         }
+        self.len += 1;
     }
 
     /// Removes a value from the set. Returns `true` if the value was present in the set.
@@ -173,6 +179,7 @@ impl RoaringTreemap {
                     if ent.get().is_empty() {
                         ent.remove();
                     }
+                    self.len -= 1;
                     true
                 } else {
                     false
@@ -224,6 +231,7 @@ impl RoaringTreemap {
             self.map.remove(&key);
         }
 
+        self.len -= removed;
         removed
     }
 
@@ -263,6 +271,7 @@ impl RoaringTreemap {
     /// ```
     pub fn clear(&mut self) {
         self.map.clear();
+        self.len = 0;
     }
 
     /// Returns `true` if there are no integers in this set.
@@ -279,7 +288,8 @@ impl RoaringTreemap {
     /// assert_eq!(rb.is_empty(), false);
     /// ```
     pub fn is_empty(&self) -> bool {
-        self.map.values().all(RoaringBitmap::is_empty)
+        // Buckets are removed as soon as they become empty
+        self.map.is_empty()
     }
 
     /// Returns `true` if there are every possible integers in this set.
@@ -315,7 +325,7 @@ impl RoaringTreemap {
     /// assert_eq!(rb.len(), 2);
     /// ```
     pub fn len(&self) -> u64 {
-        self.map.values().map(RoaringBitmap::len).sum()
+        self.len
     }
 
     /// Returns the minimum value in the set (if the set is non-empty).
@@ -361,6 +371,30 @@ impl RoaringTreemap {
             .map(|(k, rb)| util::join(*k, rb.max().unwrap()))
     }
 
+    /// Returns the number of bits needed to represent the set, one past its maximum value,
+    /// or 0 if the set is empty.
+    ///
+    /// The result is a `u128` as a set containing `u64::MAX` needs 2^64 bits.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let mut rb = RoaringTreemap::new();
+    /// assert_eq!(rb.bit_len(), 0);
+    ///
+    /// rb.insert(3);
+    /// rb.insert(4);
+    /// assert_eq!(rb.bit_len(), 5);
+    ///
+    /// rb.insert(u64::MAX);
+    /// assert_eq!(rb.bit_len(), 1 << 64);
+    /// ```
+    pub fn bit_len(&self) -> u128 {
+        self.max().map_or(0, |max| u128::from(max) + 1)
+    }
+
     /// Returns the number of integers that are <= value. rank(u64::MAX) == len()
     ///
     /// # Examples
@@ -377,9 +411,13 @@ impl RoaringTreemap {
     /// assert_eq!(rb.rank(10), 2)
     /// ```
     pub fn rank(&self, value: u64) -> u64 {
-        // if len becomes cached for RoaringTreemap: return len if len > value
-
         let (hi, lo) = util::split(value);
+
+        // When every bucket is below the value the rank is the whole len
+        if self.map.keys().next_back().map_or(true, |&key| key < hi) {
+            return self.len;
+        }
+
         let mut iter = self.map.range(..=hi).rev();
 
         iter.next()
@@ -416,6 +454,22 @@ impl RoaringTreemap {
 
         None
     }
+
+    /// Checks that the treemap upholds its invariants, namely that none of its buckets is empty
+    /// and that its cached len matches the len of its buckets.
+    ///
+    /// This is meant to be used in tests, it returns a description of the first broken
+    /// invariant.
+    #[doc(hidden)]
+    pub fn internal_validate(&self) -> Result<(), &'static str> {
+        if self.map.values().any(RoaringBitmap::is_empty) {
+            return Err("treemap contains an empty bucket");
+        }
+        if self.map.values().map(RoaringBitmap::len).sum::<u64>() != self.len {
+            return Err("treemap len does not match the len of its buckets");
+        }
+        Ok(())
+    }
 }
 
 impl Default for RoaringTreemap {
@@ -426,10 +480,85 @@ impl Default for RoaringTreemap {
 
 impl Clone for RoaringTreemap {
     fn clone(&self) -> Self {
-        RoaringTreemap { map: self.map.clone() }
+        RoaringTreemap { map: self.map.clone(), len: self.len }
     }
 
     fn clone_from(&mut self, other: &Self) {
         self.map.clone_from(&other.map);
+        self.len = other.len;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::RoaringTreemap;
+    use proptest::prelude::*;
+
+    #[derive(Debug, Clone)]
+    enum Mutation {
+        Insert(u64),
+        Push(u64),
+        Remove(u64),
+        InsertRange(u64, u64),
+        RemoveRange(u64, u64),
+        Or(RoaringTreemap),
+        And(RoaringTreemap),
+        Sub(RoaringTreemap),
+        Xor(RoaringTreemap),
+        Clear,
+    }
+
+    fn value() -> impl Strategy<Value = u64> {
+        (0u64..4, 0u64..70_000).prop_map(|(hi, lo)| (hi << 32) + lo)
+    }
+
+    fn treemap() -> impl Strategy<Value = RoaringTreemap> {
+        prop::collection::vec((value(), 0u64..10_000), 0..4).prop_map(|ranges| {
+            let mut treemap = RoaringTreemap::new();
+            for (start, len) in ranges {
+                treemap.insert_range(start..start + len);
+            }
+            treemap
+        })
+    }
+
+    fn mutation() -> impl Strategy<Value = Mutation> {
+        prop_oneof![
+            4 => value().prop_map(Mutation::Insert),
+            2 => value().prop_map(Mutation::Push),
+            4 => value().prop_map(Mutation::Remove),
+            2 => (value(), 0u64..10_000).prop_map(|(start, len)| Mutation::InsertRange(start, start + len)),
+            2 => (value(), 0u64..10_000).prop_map(|(start, len)| Mutation::RemoveRange(start, start + len)),
+            1 => treemap().prop_map(Mutation::Or),
+            1 => treemap().prop_map(Mutation::And),
+            1 => treemap().prop_map(Mutation::Sub),
+            1 => treemap().prop_map(Mutation::Xor),
+            1 => Just(Mutation::Clear),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn cached_len_matches_buckets(
+            mut treemap in RoaringTreemap::arbitrary(),
+            mutations in prop::collection::vec(mutation(), 0..32),
+        ) {
+            for mutation in mutations {
+                match mutation {
+                    Mutation::Insert(value) => { treemap.insert(value); }
+                    Mutation::Push(value) => { treemap.push(value); }
+                    Mutation::Remove(value) => { treemap.remove(value); }
+                    Mutation::InsertRange(start, end) => { treemap.insert_range(start..end); }
+                    Mutation::RemoveRange(start, end) => { treemap.remove_range(start..end); }
+                    Mutation::Or(other) => treemap |= other,
+                    Mutation::And(other) => treemap &= &other,
+                    Mutation::Sub(other) => treemap -= other,
+                    Mutation::Xor(other) => treemap ^= &other,
+                    Mutation::Clear => treemap.clear(),
+                }
+                prop_assert_eq!(treemap.internal_validate(), Ok(()));
+                prop_assert_eq!(treemap.clone().len(), treemap.len());
+            }
+        }
     }
 }
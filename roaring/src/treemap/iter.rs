@@ -1,9 +1,11 @@
 use alloc::collections::{btree_map, BTreeMap};
-use core::iter;
+use core::iter::{self, FusedIterator};
+use core::ops::RangeInclusive;
 
 use super::util;
 use crate::bitmap::IntoIter as IntoIter32;
 use crate::bitmap::Iter as Iter32;
+use crate::bitmap::MergeRuns;
 use crate::{NonSortedIntegers, RoaringBitmap, RoaringTreemap};
 
 struct To64Iter<'a> {
@@ -110,8 +112,8 @@ pub struct IntoIter {
 }
 
 impl Iter<'_> {
-    fn new(map: &BTreeMap<u32, RoaringBitmap>) -> Iter {
-        let size_hint: u64 = map.iter().map(|(_, r)| r.len()).sum();
+    fn new(map: &BTreeMap<u32, RoaringBitmap>) -> Iter<'_> {
+        let size_hint: u64 = map.values().map(|r| r.len()).sum();
         let i = map.iter().flat_map(to64iter as _);
         Iter { inner: i, size_hint }
     }
@@ -173,6 +175,8 @@ impl ExactSizeIterator for Iter<'_> {
     }
 }
 
+impl FusedIterator for Iter<'_> {}
+
 impl Iterator for IntoIter {
     type Item = u64;
 
@@ -221,6 +225,8 @@ impl ExactSizeIterator for IntoIter {
     }
 }
 
+impl FusedIterator for IntoIter {}
+
 impl RoaringTreemap {
     /// Iterator over each value stored in the RoaringTreemap, guarantees values are ordered by
     /// value.
@@ -238,7 +244,7 @@ impl RoaringTreemap {
     /// assert_eq!(iter.next(), Some(2));
     /// assert_eq!(iter.next(), None);
     /// ```
-    pub fn iter(&self) -> Iter {
+    pub fn iter(&self) -> Iter<'_> {
         Iter::new(&self.map)
     }
 
@@ -257,27 +263,102 @@ impl RoaringTreemap {
     /// assert_eq!(bitmaps.next(), Some((0, &(0..6000).collect::<RoaringBitmap>())));
     /// assert_eq!(bitmaps.next(), None);
     /// ```
-    pub fn bitmaps(&self) -> BitmapIter {
+    pub fn bitmaps(&self) -> BitmapIter<'_> {
         BitmapIter(self.map.iter())
     }
 
-    /// Construct a RoaringTreemap from an iterator of partition number and RoaringBitmap pairs.
+    /// Iterator over the values of a single partition, in ascending order.
     /// The partition number is defined by the 32 most significant bits of the bit index.
-    /// Note that repeated partitions, if present, will replace previously set partitions.
+    ///
+    /// The bitmap of the partition is not copied, the iterator is empty if there is no such partition.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let treemap = RoaringTreemap::from([1, 2, (1 << 32) + 3, (1 << 32) + 4, 2 << 32]);
+    ///
+    /// assert!(treemap.bucket_iter(1).eq([(1 << 32) + 3, (1 << 32) + 4]));
+    /// assert!(treemap.bucket_iter(0).rev().eq([2, 1]));
+    /// assert_eq!(treemap.bucket_iter(3).next(), None);
+    /// ```
+    pub fn bucket_iter(&self, high: u32) -> impl DoubleEndedIterator<Item = u64> + '_ {
+        self.map.get_key_value(&high).map(to64iter).into_iter().flatten()
+    }
+
+    /// Folds over the maximal runs of consecutive values of the set, in ascending order.
+    ///
+    /// The runs are merged across partition boundaries, see [`RoaringBitmap::fold_runs`].
     ///
     /// # Examples
     ///
     /// ```rust
     /// use roaring::RoaringTreemap;
+    ///
+    /// let mut treemap = RoaringTreemap::from([1, 2, 3]);
+    /// treemap.insert_range(u32::MAX as u64 - 1..(1 << 32) + 2);
+    ///
+    /// let runs = treemap.fold_runs(Vec::new(), |mut runs, run| {
+    ///     runs.push(run);
+    ///     runs
+    /// });
+    /// assert_eq!(runs, vec![1..=3, u32::MAX as u64 - 1..=(1 << 32) + 1]);
+    /// ```
+    pub fn fold_runs<B, F>(&self, init: B, f: F) -> B
+    where
+        F: FnMut(B, RangeInclusive<u64>) -> B,
+    {
+        let runs = self.map.iter().flat_map(|(&hi, bitmap)| {
+            bitmap
+                .maximal_runs()
+                .map(move |run| util::join(hi, *run.start())..=util::join(hi, *run.end()))
+        });
+        MergeRuns::new(runs).fold(init, f)
+    }
+
+    /// Construct a RoaringTreemap from an iterator of partition number and RoaringBitmap pairs.
+    /// The partition number is defined by the 32 most significant bits of the bit index.
+    ///
+    /// The bitmaps are moved into the treemap without copying their values. Repeated
+    /// partitions, if present, are merged with a union, and empty bitmaps are dropped as
+    /// the treemap never stores empty partitions.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringTreemap};
     /// use core::iter::FromIterator;
     ///
     /// let original = (0..6000).collect::<RoaringTreemap>();
     /// let clone = RoaringTreemap::from_bitmaps(original.bitmaps().map(|(p, b)| (p, b.clone())));
     ///
     /// assert_eq!(clone, original);
+    ///
+    /// let merged = RoaringTreemap::from_bitmaps([
+    ///     (1, RoaringBitmap::from([1, 2])),
+    ///     (0, RoaringBitmap::new()),
+    ///     (1, RoaringBitmap::from([3])),
+    /// ]);
+    /// assert_eq!(merged, RoaringTreemap::from([(1 << 32) + 1, (1 << 32) + 2, (1 << 32) + 3]));
+    /// assert_eq!(merged.bitmaps().count(), 1);
     /// ```
     pub fn from_bitmaps<I: IntoIterator<Item = (u32, RoaringBitmap)>>(iterator: I) -> Self {
-        RoaringTreemap { map: iterator.into_iter().collect() }
+        let mut map = BTreeMap::new();
+        for (partition, bitmap) in iterator {
+            if bitmap.is_empty() {
+                continue;
+            }
+            match map.entry(partition) {
+                btree_map::Entry::Vacant(entry) => {
+                    entry.insert(bitmap);
+                }
+                btree_map::Entry::Occupied(mut entry) => {
+                    *entry.get_mut() |= bitmap;
+                }
+            }
+        }
+        RoaringTreemap::from_map(map)
     }
 }
 
@@ -429,6 +510,8 @@ impl<'a> Iterator for BitmapIter<'a> {
     }
 }
 
+impl FusedIterator for BitmapIter<'_> {}
+
 impl FromIterator<(u32, RoaringBitmap)> for RoaringTreemap {
     fn from_iter<I: IntoIterator<Item = (u32, RoaringBitmap)>>(iterator: I) -> RoaringTreemap {
         Self::from_bitmaps(iterator)
@@ -39,4 +39,14 @@ pub use self::iter::{IntoIter, Iter};
 #[derive(PartialEq)]
 pub struct RoaringTreemap {
     map: BTreeMap<u32, RoaringBitmap>,
+    /// The sum of the lens of every bucket, kept up to date by every mutation.
+    len: u64,
+}
+
+impl RoaringTreemap {
+    /// Creates a treemap from buckets that are known to be non-empty, computing its len.
+    fn from_map(map: BTreeMap<u32, RoaringBitmap>) -> RoaringTreemap {
+        let len = map.values().map(RoaringBitmap::len).sum();
+        RoaringTreemap { map, len }
+    }
 }
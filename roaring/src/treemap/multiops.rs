@@ -117,7 +117,7 @@ where
         }
     }
 
-    Ok(RoaringTreemap { map })
+    Ok(RoaringTreemap::from_map(map))
 }
 
 #[inline]
@@ -138,11 +138,14 @@ where
     for k in keys {
         // the unwrap is safe since we're iterating on our keys
         let current_bitmap = treemap.map.remove(&k).unwrap();
+        let current_len = current_bitmap.len();
         let new_bitmap =
             O::op_owned(core::iter::once(current_bitmap).chain(
                 treemaps.iter_mut().map(|treemap| treemap.map.remove(&k).unwrap_or_default()),
             ));
+        treemap.len -= current_len;
         if !new_bitmap.is_empty() {
+            treemap.len += new_bitmap.len();
             treemap.map.insert(k, new_bitmap);
         }
     }
@@ -175,6 +178,7 @@ where
                 .chain(treemaps.iter().map(|treemap| treemap.map.get(&k).unwrap_or(&empty_bitmap))),
         );
         if !new_bitmap.is_empty() {
+            ret.len += new_bitmap.len();
             ret.map.insert(k, new_bitmap);
         }
     }
@@ -235,7 +239,7 @@ where
         }
     }
 
-    Ok(RoaringTreemap { map })
+    Ok(RoaringTreemap::from_map(map))
 }
 
 trait Op {
@@ -158,10 +158,13 @@ impl BitOrAssign<RoaringTreemap> for RoaringTreemap {
         for (key, other_rb) in rhs.map {
             match self.map.entry(key) {
                 Entry::Vacant(ent) => {
+                    self.len += other_rb.len();
                     ent.insert(other_rb);
                 }
                 Entry::Occupied(mut ent) => {
+                    self.len -= ent.get().len();
                     BitOrAssign::bitor_assign(ent.get_mut(), other_rb);
+                    self.len += ent.get().len();
                 }
             }
         }
@@ -174,10 +177,13 @@ impl BitOrAssign<&RoaringTreemap> for RoaringTreemap {
         for (key, other_rb) in &rhs.map {
             match self.map.entry(*key) {
                 Entry::Vacant(ent) => {
+                    self.len += other_rb.len();
                     ent.insert(other_rb.clone());
                 }
                 Entry::Occupied(mut ent) => {
+                    self.len -= ent.get().len();
                     BitOrAssign::bitor_assign(ent.get_mut(), other_rb);
+                    self.len += ent.get().len();
                 }
             }
         }
@@ -245,12 +251,17 @@ impl BitAndAssign<&RoaringTreemap> for RoaringTreemap {
         for (key, self_rb) in &mut self.map {
             match rhs.map.get(key) {
                 Some(other_rb) => {
+                    self.len -= self_rb.len();
                     BitAndAssign::bitand_assign(self_rb, other_rb);
+                    self.len += self_rb.len();
                     if self_rb.is_empty() {
                         keys_to_remove.push(*key);
                     }
                 }
-                None => keys_to_remove.push(*key),
+                None => {
+                    self.len -= self_rb.len();
+                    keys_to_remove.push(*key);
+                }
             }
         }
 
@@ -312,7 +323,9 @@ impl SubAssign<&RoaringTreemap> for RoaringTreemap {
             match self.map.entry(*key) {
                 Entry::Vacant(_entry) => (),
                 Entry::Occupied(mut entry) => {
+                    self.len -= entry.get().len();
                     SubAssign::sub_assign(entry.get_mut(), rhs_rb);
+                    self.len += entry.get().len();
                     if entry.get().is_empty() {
                         entry.remove_entry();
                     }
@@ -370,10 +383,13 @@ impl BitXorAssign<RoaringTreemap> for RoaringTreemap {
         for (key, other_rb) in rhs.map {
             match self.map.entry(key) {
                 Entry::Vacant(entry) => {
+                    self.len += other_rb.len();
                     entry.insert(other_rb);
                 }
                 Entry::Occupied(mut entry) => {
+                    self.len -= entry.get().len();
                     BitXorAssign::bitxor_assign(entry.get_mut(), other_rb);
+                    self.len += entry.get().len();
                     if entry.get().is_empty() {
                         entry.remove_entry();
                     }
@@ -389,10 +405,13 @@ impl BitXorAssign<&RoaringTreemap> for RoaringTreemap {
         for (key, other_rb) in &rhs.map {
             match self.map.entry(*key) {
                 Entry::Vacant(entry) => {
+                    self.len += other_rb.len();
                     entry.insert(other_rb.clone());
                 }
                 Entry::Occupied(mut entry) => {
+                    self.len -= entry.get().len();
                     BitXorAssign::bitxor_assign(entry.get_mut(), other_rb);
+                    self.len += entry.get().len();
                     if entry.get().is_empty() {
                         entry.remove_entry();
                     }
@@ -50,7 +50,7 @@ impl Serialize for RoaringTreemap {
     where
         S: serde::Serializer,
     {
-        let mut buf = Vec::new();
+        let mut buf = Vec::with_capacity(self.serialized_size());
         self.serialize_into(&mut buf).map_err(serde::ser::Error::custom)?;
 
         serializer.serialize_bytes(&buf)
@@ -78,5 +78,18 @@ mod test {
             let buffer = bincode::serialize(&treemap).unwrap();
             prop_assert_eq!(treemap, bincode::deserialize(&buffer).unwrap());
         }
+
+        #[test]
+        fn test_serialize_as_one_blob(
+            treemap in RoaringTreemap::arbitrary(),
+        ) {
+            let mut expected = Vec::new();
+            treemap.serialize_into(&mut expected).unwrap();
+
+            // bincode prefixes byte blobs with their length
+            let buffer = bincode::serialize(&treemap).unwrap();
+            prop_assert_eq!(&buffer[..8], &(expected.len() as u64).to_le_bytes());
+            prop_assert_eq!(&buffer[8..], &expected[..]);
+        }
     }
 }
@@ -28,6 +28,10 @@ impl RoaringTreemap {
     /// Serialize this bitmap.
     /// This is compatible with the official C/C++, Java and Go implementations.
     ///
+    /// The bucket count is written first, then each bucket is streamed to the writer as its
+    /// key followed by its [`RoaringBitmap::serialize_into`] bytes, nothing is buffered.
+    /// Wrap the writer in a [`io::BufWriter`] if it performs a syscall per write.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -56,6 +60,9 @@ impl RoaringTreemap {
     /// This is compatible with the official C/C++, Java and Go implementations.
     /// This method checks that all of the internal values are valid.
     ///
+    /// The buckets are read one at a time from the reader, which is left right after
+    /// the serialized bitmap.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -110,7 +117,11 @@ impl RoaringTreemap {
             let key = reader.read_u32::<LittleEndian>()?;
             let bitmap = deserialize_bitmap(&mut reader)?;
 
-            s.map.insert(key, bitmap);
+            // Other implementations may serialize empty buckets, we never keep them
+            if !bitmap.is_empty() {
+                s.len += bitmap.len();
+                s.map.insert(key, bitmap);
+            }
         }
 
         Ok(s)
@@ -99,3 +99,31 @@ fn bitmaps() {
 
     assert_eq!(bitmap1, bitmap3);
 }
+
+#[test]
+fn intersect_with_method() {
+    let mut bitmap1 = (0..2000).chain(100_000..200_000).collect::<RoaringBitmap>();
+    let bitmap2 = (1000..8000).chain(150_000..150_010).collect::<RoaringBitmap>();
+    let bitmap3 = (1000..2000).chain(150_000..150_010).collect::<RoaringBitmap>();
+
+    let mut bitmap4 = bitmap2.clone();
+    bitmap4.intersect_with(&bitmap1);
+    bitmap1.intersect_with(&bitmap2);
+
+    assert_eq!(bitmap1, bitmap3);
+    assert_eq!(bitmap4, bitmap3);
+}
+
+#[test]
+fn intersect_with_method_bitmap_and_array() {
+    // A bitset container of self intersected with an array container of other
+    let mut bitmap1 = (0..10_000).chain(70_000..80_000).collect::<RoaringBitmap>();
+    let bitmap2 = (5000..5100).chain([70_000, 79_999, 90_000]).collect::<RoaringBitmap>();
+    let bitmap3 = (5000..5100).chain([70_000, 79_999]).collect::<RoaringBitmap>();
+
+    bitmap1.intersect_with(&bitmap2);
+
+    assert_eq!(bitmap1, bitmap3);
+    assert_eq!(bitmap1.validate(), Ok(()));
+    assert_eq!(bitmap1.statistics().n_bitset_containers, 0);
+}
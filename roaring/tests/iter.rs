@@ -67,6 +67,23 @@ proptest! {
     }
 }
 
+proptest! {
+    #[test]
+    fn extend_sorted_then_unsorted(
+        initial in btree_set(0..0x3_0000u32, ..=100),
+        sorted in btree_set(0..0x3_0000u32, ..=10_000),
+        unsorted in proptest::collection::vec(0..0x3_0000u32, ..=100),
+    ) {
+        let mut bitmap = RoaringBitmap::from_sorted_iter(initial.iter().cloned()).unwrap();
+        bitmap.extend(sorted.iter().chain(&unsorted));
+
+        let mut expected = initial;
+        expected.extend(sorted.iter().chain(&unsorted));
+        assert!(expected.into_iter().eq(bitmap.iter()));
+        assert_eq!(bitmap.validate(), Ok(()));
+    }
+}
+
 proptest! {
     #[test]
     fn fold(values in btree_set(any::<u32>(), ..=10_000)) {
@@ -104,6 +121,36 @@ proptest! {
     }
 }
 
+proptest! {
+    #[test]
+    fn nth_dense(values in btree_set(0..200_000u32, ..=50_000), nth in 0..50_005usize) {
+        let bitmap = RoaringBitmap::from_sorted_iter(values.iter().cloned()).unwrap();
+        let mut iter = bitmap.iter();
+        let mut val_iter = values.iter().cloned();
+
+        assert_eq!(val_iter.nth(nth), iter.nth(nth));
+        assert_eq!(val_iter.next(), iter.next());
+        assert_eq!(val_iter.nth(nth / 2), iter.nth(nth / 2));
+        assert_eq!(val_iter.next_back(), iter.next_back());
+        assert!(val_iter.eq(iter));
+    }
+}
+
+proptest! {
+    #[test]
+    fn nth_back(values in btree_set(0..200_000u32, ..=50_000), nth in 0..50_005usize) {
+        let bitmap = RoaringBitmap::from_sorted_iter(values.iter().cloned()).unwrap();
+        let mut iter = bitmap.iter();
+        let mut val_iter = values.iter().cloned();
+
+        assert_eq!(val_iter.nth_back(nth), iter.nth_back(nth));
+        assert_eq!(val_iter.next_back(), iter.next_back());
+        assert_eq!(val_iter.nth_back(nth / 2), iter.nth_back(nth / 2));
+        assert_eq!(val_iter.next(), iter.next());
+        assert!(val_iter.rev().eq(iter.rev()));
+    }
+}
+
 #[test]
 fn huge_nth() {
     let bitmap = RoaringBitmap::new();
@@ -111,6 +158,18 @@ fn huge_nth() {
     assert_eq!(None, iter.nth(usize::MAX));
 }
 
+#[test]
+fn from_iter_with_capacity() {
+    let values = [5, 70_000, 1, 1 << 20, 70_000];
+    let expected = values.iter().collect::<RoaringBitmap>();
+
+    assert_eq!(RoaringBitmap::from_iter_with_capacity(values, 1 << 20), expected);
+    // The hint does not limit the values
+    assert_eq!(RoaringBitmap::from_iter_with_capacity(values, 10), expected);
+    assert_eq!(RoaringBitmap::from_iter_with_capacity(values, u32::MAX), expected);
+    assert!(RoaringBitmap::from_iter_with_capacity([], u32::MAX).is_empty());
+}
+
 proptest! {
     #[test]
     fn count(values in btree_set(any::<u32>(), ..=10_000), skip in 0..10_005usize) {
@@ -153,6 +212,267 @@ proptest! {
     }
 }
 
+proptest! {
+    #[test]
+    fn rev_mixed_containers(
+        values in btree_set(0..0x4_0000u32, ..=10_000),
+        dense_start in 0..0x4_0000u32,
+        lo in 0..0x5_0000u32,
+        hi in 0..0x5_0000u32,
+    ) {
+        // The dense range always produces at least one bitmap container
+        let mut bitmap = RoaringBitmap::from_sorted_iter(values.iter().cloned()).unwrap();
+        bitmap.insert_range(dense_start..dense_start + 20_000);
+        let forward: Vec<u32> = bitmap.iter().collect();
+
+        let mut reversed: Vec<u32> = bitmap.iter().rev().collect();
+        reversed.reverse();
+        assert_eq!(&forward, &reversed);
+
+        let mut reversed: Vec<u32> = bitmap.clone().into_iter().rev().collect();
+        reversed.reverse();
+        assert_eq!(&forward, &reversed);
+
+        // Partially consume from both ends before iterating in both directions
+        let expected: Vec<u32> = forward.iter().cloned().filter(|&v| lo <= v && v <= hi).collect();
+
+        let mut iter = bitmap.iter();
+        iter.advance_to(lo);
+        iter.advance_back_to(hi);
+        assert_eq!(iter.clone().collect::<Vec<u32>>(), expected);
+        assert!(expected.iter().rev().cloned().eq(iter.rev()));
+
+        let mut iter = bitmap.clone().into_iter();
+        iter.advance_back_to(hi);
+        iter.advance_to(lo);
+        assert_eq!(iter.clone().collect::<Vec<u32>>(), expected);
+        assert!(expected.iter().rev().cloned().eq(iter.rev()));
+    }
+}
+
+proptest! {
+    #[test]
+    fn peek(
+        values in btree_set(0..0x4_0000u32, ..=10_000),
+        dense_start in 0..0x4_0000u32,
+        lo in 0..0x5_0000u32,
+        hi in 0..0x5_0000u32,
+    ) {
+        let mut bitmap = RoaringBitmap::from_sorted_iter(values.iter().cloned()).unwrap();
+        bitmap.insert_range(dense_start..dense_start + 20_000);
+
+        let mut iter = bitmap.iter();
+        iter.advance_to(lo);
+        iter.advance_back_to(hi);
+        // Alternate between both ends, like in `outside_in`
+        let mut forward = true;
+        loop {
+            let (peeked, next) = if forward {
+                (iter.peek(), iter.next())
+            } else {
+                (iter.peek_back(), iter.next_back())
+            };
+            assert_eq!(peeked, next);
+            if next.is_none() {
+                break;
+            }
+            forward = !forward;
+        }
+        assert_eq!(iter.peek(), None);
+        assert_eq!(iter.peek_back(), None);
+
+        let mut iter = bitmap.into_iter();
+        iter.advance_back_to(hi);
+        iter.advance_to(lo);
+        loop {
+            assert_eq!(iter.peek_back(), iter.clone().next_back());
+            let peeked = iter.peek();
+            assert_eq!(peeked, iter.next());
+            if peeked.is_none() {
+                break;
+            }
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn fill_buffer(
+        values in btree_set(0..0x4_0000u32, ..=10_000),
+        dense_start in 0..0x4_0000u32,
+        lo in 0..0x5_0000u32,
+        hi in 0..0x5_0000u32,
+        max in 1..10_000usize,
+    ) {
+        let mut bitmap = RoaringBitmap::from_sorted_iter(values.iter().cloned()).unwrap();
+        bitmap.insert_range(dense_start..dense_start + 20_000);
+
+        let mut iter = bitmap.iter();
+        iter.advance_to(lo);
+        iter.advance_back_to(hi);
+        let last = iter.next_back();
+        let expected: Vec<u32> = iter.clone().collect();
+
+        let mut buf = Vec::new();
+        loop {
+            let len = buf.len();
+            let pushed = iter.fill_buffer(&mut buf, max);
+            assert_eq!(pushed, buf.len() - len);
+            if pushed < max {
+                break;
+            }
+        }
+        assert_eq!(buf, expected);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.fill_buffer(&mut buf, max), 0);
+
+        let mut iter = bitmap.into_iter();
+        iter.advance_to(lo);
+        iter.advance_back_to(hi);
+        assert_eq!(iter.next_back(), last);
+        let mut buf = Vec::new();
+        while iter.fill_buffer(&mut buf, max) == max {}
+        assert_eq!(buf, expected);
+    }
+}
+
+#[test]
+fn fill_buffer_full() {
+    let bitmap = RoaringBitmap::full();
+    let mut iter = bitmap.iter();
+    iter.advance_to(u32::MAX - 199);
+
+    let mut buf = Vec::new();
+    assert_eq!(iter.fill_buffer(&mut buf, 100), 100);
+    assert_eq!(iter.fill_buffer(&mut buf, 1000), 100);
+    assert_eq!(buf, (u32::MAX - 199..=u32::MAX).collect::<Vec<_>>());
+    assert_eq!(iter.fill_buffer(&mut buf, 0), 0);
+}
+
+proptest! {
+    #[test]
+    fn drain(
+        values in btree_set(0..0x4_0000u32, ..=10_000),
+        dense_start in 0..0x4_0000u32,
+        lo in 0..0x5_0000u32,
+        hi in 0..0x5_0000u32,
+        take in 0..25_000usize,
+    ) {
+        let mut bitmap = RoaringBitmap::from_sorted_iter(values.iter().cloned()).unwrap();
+        bitmap.insert_range(dense_start..dense_start + 20_000);
+        let (lo, hi) = (lo.min(hi), lo.max(hi));
+
+        // Only the yielded values are removed
+        let mut remaining = bitmap.clone();
+        let drained: Vec<u32> = remaining.drain_range(lo..hi).take(take).collect();
+        let expected: Vec<u32> = bitmap.range(lo..hi).take(take).collect();
+        assert_eq!(&drained, &expected);
+        assert_eq!(remaining, &bitmap - &RoaringBitmap::from_iter(drained));
+
+        let mut remaining = bitmap.clone();
+        assert!(remaining.drain().eq(bitmap.iter()));
+        assert!(remaining.is_empty());
+    }
+}
+
+#[test]
+fn drain_full_range() {
+    let mut bitmap = RoaringBitmap::from([0, u32::MAX]);
+    assert!(bitmap.drain_range(..).eq([0, u32::MAX]));
+    assert!(bitmap.is_empty());
+
+    let mut bitmap = RoaringBitmap::from([0, u32::MAX]);
+    assert!(bitmap.drain_range(1..1).eq([]));
+    assert_eq!(bitmap.drain_range(u32::MAX..).next(), Some(u32::MAX));
+    assert_eq!(bitmap, RoaringBitmap::from([0]));
+}
+
+proptest! {
+    #[test]
+    fn fold_runs(
+        values in btree_set(0..0x4_0000u32, ..=10_000),
+        dense_start in 0..0x4_0000u32,
+        dense_len in 0..100_000u32,
+    ) {
+        let mut bitmap = RoaringBitmap::from_sorted_iter(values.iter().cloned()).unwrap();
+        bitmap.insert_range(dense_start..dense_start + dense_len);
+
+        let mut expected: Vec<(u32, u32)> = Vec::new();
+        for value in &bitmap {
+            match expected.last_mut() {
+                Some((_, end)) if *end + 1 == value => *end = value,
+                _ => expected.push((value, value)),
+            }
+        }
+
+        let runs = bitmap.fold_runs(Vec::new(), |mut runs, run| {
+            runs.push((*run.start(), *run.end()));
+            runs
+        });
+        assert_eq!(runs, expected);
+
+        let runs: Vec<_> = bitmap.maximal_runs().map(|run| (*run.start(), *run.end())).collect();
+        assert_eq!(runs, expected);
+    }
+}
+
+#[test]
+fn fold_runs_boundaries() {
+    let collect = |bitmap: &RoaringBitmap| {
+        bitmap.fold_runs(Vec::new(), |mut runs, run| {
+            runs.push(run);
+            runs
+        })
+    };
+
+    assert!(collect(&RoaringBitmap::new()).is_empty());
+    assert_eq!(collect(&RoaringBitmap::full()), vec![0..=u32::MAX]);
+
+    // A run spanning a bitset and an array container, and word boundaries in a bitset
+    let mut bitmap = RoaringBitmap::from([63, 64, 127]);
+    bitmap.insert_range(60_000..=65_536);
+    bitmap.insert(u32::MAX);
+    assert_eq!(collect(&bitmap), vec![63..=64, 127..=127, 60_000..=65_536, u32::MAX..=u32::MAX]);
+
+    let sum = bitmap.fold_runs(0u64, |sum, run| {
+        let (start, end) = (u64::from(*run.start()), u64::from(*run.end()));
+        sum + (start + end) * (end - start + 1) / 2
+    });
+    assert_eq!(sum, bitmap.iter().map(u64::from).sum());
+}
+
+#[test]
+fn maximal_runs_boundaries() {
+    let collect = |bitmap: &RoaringBitmap| bitmap.maximal_runs().collect::<Vec<_>>();
+
+    assert!(collect(&RoaringBitmap::new()).is_empty());
+    assert_eq!(collect(&RoaringBitmap::full()), vec![0..=u32::MAX]);
+
+    // Runs straddling container keys, between arrays, bitsets and both
+    let mut bitmap = RoaringBitmap::from_iter((65_530..=65_540).chain([131_071, 131_072]));
+    bitmap.insert_range(196_000..=300_000);
+    bitmap.insert_range(393_000..=393_215);
+    bitmap.insert(u32::MAX);
+    assert_eq!(
+        collect(&bitmap),
+        vec![
+            65_530..=65_540,
+            131_071..=131_072,
+            196_000..=300_000,
+            393_000..=393_215,
+            u32::MAX..=u32::MAX
+        ]
+    );
+
+    // A run ending a container that is not followed by the next key
+    let bitmap = RoaringBitmap::from([65_535, 131_073]);
+    assert_eq!(collect(&bitmap), vec![65_535..=65_535, 131_073..=131_073]);
+
+    let mut runs = bitmap.maximal_runs();
+    assert_eq!(runs.by_ref().count(), 2);
+    assert_eq!(runs.next(), None);
+}
+
 #[test]
 fn from_iter() {
     // This test verifies that the public API allows conversion from iterators
@@ -210,6 +530,27 @@ fn interleaved_bitmap() {
     assert!(outside_in(values).eq(outside_in(bitmap)));
 }
 
+#[test]
+fn fused() {
+    let bitmap = (0..10).chain(100_000..110_000).collect::<RoaringBitmap>();
+
+    let mut iter = bitmap.iter();
+    assert_eq!(iter.by_ref().count(), 10_010);
+    for _ in 0..3 {
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+
+    // The front and back cursors meet in the middle of a container
+    let mut iter = bitmap.clone().into_iter();
+    assert_eq!(iter.nth(5), Some(5));
+    assert_eq!(iter.nth_back(10_003), Some(6));
+    for _ in 0..3 {
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+    }
+}
+
 proptest! {
     #[test]
     fn interleaved_iter(values in btree_set(any::<u32>(), 50_000..=100_000)) {
@@ -206,3 +206,21 @@ fn advance_bitset_back_to_start_word() {
     }
     assert_eq!(iter.next(), None);
 }
+
+#[test]
+fn advance_bitset_past_back() {
+    let mut bitmap = RoaringBitmap::new();
+    bitmap.insert_range(0x2000..0x4000);
+    let mut iter = bitmap.iter();
+    iter.advance_back_to(0x5000);
+    // Advancing the front past the back of the iterator exhausts it
+    iter.advance_to(0x7000);
+    assert_eq!(iter.clone().next(), None);
+    assert_eq!(iter.next_back(), None);
+
+    let mut iter = bitmap.iter();
+    assert_eq!(iter.next_back(), Some(0x3fff));
+    iter.advance_to(0x4000);
+    assert_eq!(iter.len(), 0);
+    assert_eq!(iter.next(), None);
+}
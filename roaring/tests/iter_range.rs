@@ -93,3 +93,46 @@ proptest! {
         assert_eq!(expected, actual);
     }
 }
+
+#[test]
+fn dense_iter_bounds() {
+    let rb = RoaringBitmap::from([0, 65_535, 65_536, u32::MAX]);
+
+    assert!(rb.dense_iter(..2).eq([(0, true), (1, false)]));
+    assert!(rb.dense_iter(65_534..=65_537).eq([
+        (65_534, false),
+        (65_535, true),
+        (65_536, true),
+        (65_537, false)
+    ]));
+    assert!(rb.dense_iter(u32::MAX - 1..).eq([(u32::MAX - 1, false), (u32::MAX, true)]));
+    assert_eq!(rb.dense_iter(13..13).next(), None);
+    assert_eq!(rb.dense_iter((Bound::Excluded(u32::MAX), Bound::Unbounded)).next(), None);
+}
+
+#[test]
+#[should_panic(expected = "range start is greater than range end")]
+fn dense_iter_invalid_range() {
+    let rb = RoaringBitmap::from_sorted_iter(10..5000).unwrap();
+    #[allow(clippy::reversed_empty_ranges)]
+    let _ = rb.dense_iter(13..0);
+}
+
+proptest! {
+    #[test]
+    fn proptest_dense_iter(
+        values in btree_set(..=262_143_u32, ..=1000),
+        dense_start in 0u32..262_143,
+        range_a in 0u32..262_143,
+        range_b in 0u32..262_143,
+    ){
+        let range = range_a.min(range_b)..=range_a.max(range_b);
+
+        let mut bitmap = RoaringBitmap::from_sorted_iter(values.iter().cloned()).unwrap();
+        bitmap.insert_range(dense_start..dense_start + 10_000);
+        let expected: Vec<(u32, bool)> = range.clone().map(|value| (value, bitmap.contains(value))).collect();
+        let actual: Vec<(u32, bool)> = bitmap.dense_iter(range).collect();
+
+        assert_eq!(expected, actual);
+    }
+}
@@ -1,4 +1,5 @@
 extern crate roaring;
+use roaring::bitmap::ContainerKind;
 use roaring::RoaringBitmap;
 
 #[test]
@@ -33,6 +34,91 @@ fn smoke() {
     assert!(bitmap.contains(u32::MAX));
 }
 
+#[test]
+fn get_or_insert() {
+    let mut rb = (0..5000).collect::<RoaringBitmap>();
+
+    assert!(rb.get_or_insert(10));
+    assert!(!rb.get_or_insert(5000));
+    assert!(!rb.get_or_insert(u32::MAX));
+    assert!(rb.get_or_insert(u32::MAX));
+    assert!(rb.contains(5000));
+    assert!(rb.contains(u32::MAX));
+    assert_eq!(rb.len(), 5002);
+}
+
+#[test]
+fn membership() {
+    let rb = (0..10).chain(100_000..110_000).collect::<RoaringBitmap>();
+    let values = [u32::MAX, 5, 10, 99_999, 100_000, 105_000, 0, 109_999, 110_000, 5];
+
+    let bools = rb.membership_bools(&values);
+    assert_eq!(bools, values.iter().map(|&value| rb.contains(value)).collect::<Vec<_>>());
+    assert_eq!(bools, [false, true, false, false, true, true, true, true, false, true]);
+    assert!(RoaringBitmap::new().membership_bools(&values).iter().all(|&b| !b));
+    assert!(rb.membership_bools(&[]).is_empty());
+
+    let query = values.iter().copied().collect::<RoaringBitmap>();
+    assert_eq!(rb.membership_mask(&query), RoaringBitmap::from([0, 5, 100_000, 105_000, 109_999]));
+}
+
+#[test]
+fn membership_bitset_batches() {
+    // Clustered queries in a bitset container, batches longer than a vector and a remainder
+    let rb = (0..70_000).filter(|value| value % 3 != 0).collect::<RoaringBitmap>();
+    let values = (60_000..60_027).chain(65_530..65_545).chain([0, 1, 69_999]).collect::<Vec<_>>();
+
+    let bools = rb.membership_bools(&values);
+    assert_eq!(
+        bools,
+        values.iter().map(|&value| value % 3 != 0 && value < 70_000).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn insert_with_outcome() {
+    let mut rb = RoaringBitmap::new();
+
+    let outcome = rb.insert_with_outcome(1);
+    assert!(outcome.was_new);
+    assert_eq!(outcome.container_converted, None);
+    assert!(!rb.insert_with_outcome(1).was_new);
+
+    for i in 2..=4096 {
+        assert_eq!(rb.insert_with_outcome(i).container_converted, None);
+    }
+    let outcome = rb.insert_with_outcome(4097);
+    assert!(outcome.was_new);
+    assert_eq!(outcome.container_converted, Some((ContainerKind::Array, ContainerKind::Bitset)));
+
+    let outcome = rb.insert_with_outcome(4098);
+    assert_eq!(outcome.container_converted, None);
+
+    // A new container is not a conversion
+    let outcome = rb.insert_with_outcome(1 << 16);
+    assert!(outcome.was_new);
+    assert_eq!(outcome.container_converted, None);
+    assert_eq!(rb.len(), 4099);
+}
+
+#[test]
+fn insert_range_tracked() {
+    let mut rb = RoaringBitmap::new();
+    assert_eq!(rb.insert_range_tracked(..0), (0, false));
+
+    // New containers are not conversions, even when created as bitsets
+    assert_eq!(rb.insert_range_tracked(0..10), (10, false));
+    assert_eq!(rb.insert_range_tracked(1 << 16..3 << 16), (2 << 16, false));
+
+    // An array growing into a bitset spanning several containers
+    assert_eq!(rb.insert_range_tracked(5..(4 << 16) + 1), ((2 << 16) + 1 - 10, true));
+    assert_eq!(rb.insert_range_tracked(0..=(4 << 16)), (0, false));
+
+    let mut expected = RoaringBitmap::new();
+    expected.insert_range(0..=(4 << 16));
+    assert_eq!(rb, expected);
+}
+
 #[test]
 fn remove_range() {
     let ranges = [0u32, 1, 63, 64, 65, 100, 4096 - 1, 4096, 4096 + 1, 65536 - 1, 65536, 65536 + 1];
@@ -121,3 +207,17 @@ fn to_array() {
         assert!(!bitmap.contains(i));
     }
 }
+
+#[test]
+fn container_words() {
+    let mut bitmap = (0..5000).map(|value| value * 7).collect::<RoaringBitmap>();
+    bitmap.insert_range(3 << 16..(3 << 16) + 100);
+
+    let words = bitmap.container_words(0).unwrap();
+    assert_eq!(words.iter().map(|word| u64::from(word.count_ones())).sum::<u64>(), 5000);
+    assert!((0..65536).all(|i| (words[i / 64] >> (i % 64) & 1 == 1) == bitmap.contains(i as u32)));
+
+    assert_eq!(bitmap.container_words(3), None);
+    bitmap.insert_range(3 << 16..4 << 16);
+    assert_eq!(bitmap.container_words(3), Some(&[u64::MAX; 1024][..]));
+}
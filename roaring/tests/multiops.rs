@@ -0,0 +1,148 @@
+extern crate roaring;
+use std::convert::Infallible;
+
+use roaring::{MultiOps, RoaringBitmap, RoaringTreemap};
+
+#[test]
+fn empty_bitmaps() {
+    let empty = RoaringBitmap::new();
+
+    assert_eq!(Vec::<RoaringBitmap>::new().union(), empty);
+    assert_eq!(Vec::<RoaringBitmap>::new().intersection(), empty);
+    assert_eq!(Vec::<RoaringBitmap>::new().difference(), empty);
+    assert_eq!(Vec::<RoaringBitmap>::new().symmetric_difference(), empty);
+
+    assert_eq!(Vec::<&RoaringBitmap>::new().union(), empty);
+    assert_eq!(Vec::<&RoaringBitmap>::new().intersection(), empty);
+    assert_eq!(Vec::<&RoaringBitmap>::new().difference(), empty);
+    assert_eq!(Vec::<&RoaringBitmap>::new().symmetric_difference(), empty);
+
+    let none = || Vec::<Result<RoaringBitmap, Infallible>>::new();
+    assert_eq!(none().union(), Ok(empty.clone()));
+    assert_eq!(none().intersection(), Ok(empty.clone()));
+    assert_eq!(none().difference(), Ok(empty.clone()));
+    assert_eq!(none().symmetric_difference(), Ok(empty));
+}
+
+#[test]
+fn single_bitmap() {
+    let bitmap: RoaringBitmap = (0..10).chain(100_000..200_000).collect();
+
+    assert_eq!([bitmap.clone()].union(), bitmap);
+    assert_eq!([bitmap.clone()].intersection(), bitmap);
+    assert_eq!([bitmap.clone()].difference(), bitmap);
+    assert_eq!([bitmap.clone()].symmetric_difference(), bitmap);
+
+    assert_eq!([&bitmap].union(), bitmap);
+    assert_eq!([&bitmap].intersection(), bitmap);
+    assert_eq!([&bitmap].difference(), bitmap);
+    assert_eq!([&bitmap].symmetric_difference(), bitmap);
+
+    let one = || [Ok::<_, Infallible>(bitmap.clone())];
+    assert_eq!(one().union(), Ok(bitmap.clone()));
+    assert_eq!(one().intersection(), Ok(bitmap.clone()));
+    assert_eq!(one().difference(), Ok(bitmap.clone()));
+    assert_eq!(one().symmetric_difference(), Ok(bitmap));
+}
+
+#[test]
+fn in_place_bitmaps() {
+    let bitmaps: Vec<RoaringBitmap> = vec![
+        (0..5000).chain(100_000..100_010).collect(),
+        (2000..3000).chain(1 << 20..(1 << 20) + 5000).collect(),
+        (0..10_000).step_by(3).collect(),
+        RoaringBitmap::from([4999, 200_000, u32::MAX]),
+    ];
+
+    for i in 0..bitmaps.len() {
+        let (lhs, others) = (&bitmaps[i], bitmaps[i + 1..].to_vec());
+        let operands = || std::iter::once(lhs.clone()).chain(others.clone());
+
+        let mut union = lhs.clone();
+        union.union_all(others.clone());
+        assert_eq!(union, operands().union());
+
+        let mut intersection = lhs.clone();
+        intersection.intersect_all(others.clone());
+        assert_eq!(intersection, operands().intersection());
+
+        let mut difference = lhs.clone();
+        difference.difference_all(others.clone());
+        assert_eq!(difference, operands().difference());
+    }
+
+    let mut empty = RoaringBitmap::new();
+    empty.union_all(Vec::new());
+    assert!(empty.is_empty());
+    empty.union_all(bitmaps.clone());
+    assert_eq!(empty, bitmaps.union());
+}
+
+#[test]
+fn symmetric_difference_parity() {
+    // Overlapping bitmaps with array and bitset containers
+    let bitmaps: Vec<RoaringBitmap> = vec![
+        (0..10_000).collect(),
+        (5000..15_000).chain(1 << 20..(1 << 20) + 10).collect(),
+        (0..20_000).step_by(2).collect(),
+        (1 << 20..(1 << 20) + 5).chain(9000..9100).collect(),
+    ];
+
+    for n in [3, 4] {
+        let expected: RoaringBitmap = bitmaps[..n]
+            .iter()
+            .flatten()
+            .filter(|&value| bitmaps[..n].iter().filter(|b| b.contains(value)).count() % 2 == 1)
+            .collect();
+
+        assert_eq!(bitmaps[..n].iter().symmetric_difference(), expected);
+        assert_eq!(bitmaps[..n].to_vec().symmetric_difference(), expected);
+        assert_eq!(
+            bitmaps[..n].iter().map(Ok::<_, Infallible>).symmetric_difference(),
+            Ok(expected.clone())
+        );
+        assert_eq!(bitmaps[..n].iter().cloned().reduce(|a, b| a ^ b).unwrap_or_default(), expected);
+    }
+}
+
+#[test]
+fn empty_treemaps() {
+    let empty = RoaringTreemap::new();
+
+    assert_eq!(Vec::<RoaringTreemap>::new().union(), empty);
+    assert_eq!(Vec::<RoaringTreemap>::new().intersection(), empty);
+    assert_eq!(Vec::<RoaringTreemap>::new().difference(), empty);
+    assert_eq!(Vec::<RoaringTreemap>::new().symmetric_difference(), empty);
+
+    assert_eq!(Vec::<&RoaringTreemap>::new().union(), empty);
+    assert_eq!(Vec::<&RoaringTreemap>::new().intersection(), empty);
+    assert_eq!(Vec::<&RoaringTreemap>::new().difference(), empty);
+    assert_eq!(Vec::<&RoaringTreemap>::new().symmetric_difference(), empty);
+
+    let none = || Vec::<Result<RoaringTreemap, Infallible>>::new();
+    assert_eq!(none().union(), Ok(empty.clone()));
+    assert_eq!(none().intersection(), Ok(empty.clone()));
+    assert_eq!(none().difference(), Ok(empty.clone()));
+    assert_eq!(none().symmetric_difference(), Ok(empty));
+}
+
+#[test]
+fn single_treemap() {
+    let treemap: RoaringTreemap = (0..10).chain(u64::MAX - 10..=u64::MAX).collect();
+
+    assert_eq!([treemap.clone()].union(), treemap);
+    assert_eq!([treemap.clone()].intersection(), treemap);
+    assert_eq!([treemap.clone()].difference(), treemap);
+    assert_eq!([treemap.clone()].symmetric_difference(), treemap);
+
+    assert_eq!([&treemap].union(), treemap);
+    assert_eq!([&treemap].intersection(), treemap);
+    assert_eq!([&treemap].difference(), treemap);
+    assert_eq!([&treemap].symmetric_difference(), treemap);
+
+    let one = || [Ok::<_, Infallible>(treemap.clone())];
+    assert_eq!(one().union(), Ok(treemap.clone()));
+    assert_eq!(one().intersection(), Ok(treemap.clone()));
+    assert_eq!(one().difference(), Ok(treemap.clone()));
+    assert_eq!(one().symmetric_difference(), Ok(treemap));
+}
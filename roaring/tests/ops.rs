@@ -37,6 +37,151 @@ fn and() {
     assert_eq!(rb3, rb1);
 }
 
+#[test]
+fn and_range() {
+    let mut rb1 = (1..4000).collect::<RoaringBitmap>();
+    rb1.insert_range(100_000..200_000);
+    rb1.insert(u32::MAX);
+
+    let rb2 = (3000..4000).chain(100_000..150_001).collect::<RoaringBitmap>();
+    assert_eq!(rb2, &rb1 & (3000..=150_000));
+    assert_eq!(rb2, rb1.clone() & (3000..=150_000));
+
+    assert_eq!(rb1, &rb1 & (0..=u32::MAX));
+    let mut rb4 = RoaringBitmap::new();
+    rb4.insert_range(60_000..70_000);
+    assert_eq!((65_535..=65_536).collect::<RoaringBitmap>(), &rb4 & (65_535..=65_536));
+
+    #[allow(clippy::reversed_empty_ranges)]
+    let empty = &rb1 & (10..=5);
+    assert!(empty.is_empty());
+    assert!((&rb1 & (4000..=99_999)).is_empty());
+
+    let mut rb3 = rb1.clone();
+    rb3 &= 3000..=150_000;
+    assert_eq!(rb2, rb3);
+
+    rb3 &= 3500..=3500;
+    assert_eq!(rb3.iter().collect::<Vec<u32>>(), vec![3500]);
+
+    rb1 &= u32::MAX..=u32::MAX;
+    assert_eq!(rb1.iter().collect::<Vec<u32>>(), vec![u32::MAX]);
+}
+
+#[test]
+fn intersection_with_all() {
+    let mut rb1 = (1..4000).collect::<RoaringBitmap>();
+    rb1.insert_range(100_000..200_000);
+    let rb2 = (0..150_000).collect::<RoaringBitmap>();
+    let rb3 = (3000..300_000).step_by(2).collect::<RoaringBitmap>();
+    let empty = RoaringBitmap::new();
+
+    let expected = &(&rb1 & &rb2) & &rb3;
+    assert_eq!(expected, rb1.intersection_with_all(&[&rb2, &rb3]));
+    assert_eq!(expected, rb1.intersection_with_all(&[&rb3, &rb2, &rb1]));
+    assert_eq!(expected, rb3.intersection_with_all(&[&rb1, &rb2]));
+
+    assert_eq!(rb1, rb1.intersection_with_all(&[]));
+    assert!(rb1.intersection_with_all(&[&rb2, &empty, &rb3]).is_empty());
+    assert!(empty.intersection_with_all(&[&rb1]).is_empty());
+}
+
+#[test]
+fn union_len_upper_bound() {
+    let mut rb1 = (1..4000).collect::<RoaringBitmap>();
+    rb1.insert_range(100_000..200_000);
+    let rb2 = (3000..150_000).step_by(3).collect::<RoaringBitmap>();
+    let rb3 = (300_000..400_000).collect::<RoaringBitmap>();
+    let empty = RoaringBitmap::new();
+
+    assert!(rb1.union_len_upper_bound(&rb2) >= rb1.union_len(&rb2));
+    assert!(rb2.union_len_upper_bound(&rb1) >= rb1.union_len(&rb2));
+
+    // Exact when disjoint
+    assert_eq!(rb1.union_len_upper_bound(&rb3), rb1.union_len(&rb3));
+    assert_eq!(rb1.union_len_upper_bound(&empty), rb1.len());
+    assert_eq!(empty.union_len_upper_bound(&empty), 0);
+
+    // Exact when the shared containers are full
+    let mut full = RoaringBitmap::new();
+    full.insert_range(0..(4 << 16));
+    assert_eq!(rb1.union_len_upper_bound(&full), rb1.union_len(&full));
+}
+
+#[test]
+fn intersection_len_shortcuts() {
+    let empty = RoaringBitmap::new();
+    let mut rb1 = (1..4000).collect::<RoaringBitmap>();
+    rb1.insert_range(100_000..200_000);
+    let rb2 = (0..300_000).collect::<RoaringBitmap>();
+
+    assert_eq!(empty.intersection_len(&rb1), 0);
+    assert_eq!(rb1.intersection_len(&empty), 0);
+    assert_eq!(empty.intersection_len(&empty), 0);
+
+    assert_eq!(rb1.intersection_len(&rb1), rb1.len());
+    assert_eq!(rb1.intersection_len(&rb1.clone()), rb1.len());
+    assert_eq!(rb1.intersection_len(&rb2), rb1.len());
+    assert_eq!(rb2.intersection_len(&rb1), rb1.len());
+}
+
+#[test]
+fn intersection_len_at_least() {
+    let empty = RoaringBitmap::new();
+    let mut rb1 = (1..4000).collect::<RoaringBitmap>();
+    rb1.insert_range(100_000..200_000);
+    let rb2 = (0..150_000).step_by(2).collect::<RoaringBitmap>();
+    let len = rb1.intersection_len(&rb2);
+
+    for threshold in [0, 1, 1999, 2000, len - 1, len, len + 1, rb2.len(), u64::MAX] {
+        assert_eq!(rb1.intersection_len_at_least(&rb2, threshold), len >= threshold);
+        assert_eq!(rb2.intersection_len_at_least(&rb1, threshold), len >= threshold);
+    }
+
+    assert!(empty.intersection_len_at_least(&rb1, 0));
+    assert!(!empty.intersection_len_at_least(&rb1, 1));
+    assert!(rb1.intersection_len_at_least(&rb1, rb1.len()));
+    assert!(!rb1.intersection_len_at_least(&rb1, rb1.len() + 1));
+}
+
+#[test]
+fn and_not_len() {
+    let empty = RoaringBitmap::new();
+    let mut rb1 = (1..4000).collect::<RoaringBitmap>();
+    rb1.insert_range(100_000..200_000);
+    let disjoint = (1 << 20..(1 << 20) + 10).collect::<RoaringBitmap>();
+    let superset = (0..300_000).collect::<RoaringBitmap>();
+
+    assert_eq!(rb1.and_not_len(&disjoint), rb1.len());
+    assert_eq!(disjoint.and_not_len(&rb1), disjoint.len());
+    assert_eq!(rb1.and_not_len(&empty), rb1.len());
+    assert_eq!(empty.and_not_len(&rb1), 0);
+
+    assert_eq!(rb1.and_not_len(&rb1), 0);
+    assert_eq!(rb1.and_not_len(&superset), 0);
+    assert_eq!(superset.and_not_len(&rb1), superset.len() - rb1.len());
+    assert_eq!(superset.and_not_len(&rb1), (&superset - &rb1).len());
+}
+
+#[test]
+#[allow(clippy::eq_op)]
+fn same_operands() {
+    let mut a = (1..1000).chain(100_000..200_000).collect::<RoaringBitmap>();
+    a.insert(u32::MAX);
+    let copy = a.clone();
+
+    assert_eq!(&a | &a, a);
+    assert_eq!(&a & &a, a);
+    assert!((&a - &a).is_empty());
+    assert!((&a ^ &a).is_empty());
+
+    // Structurally equal operands take the generic path
+    assert_eq!(&a | &copy, a);
+    assert_eq!(&a & &copy, a);
+    assert!((&a - &copy).is_empty());
+    assert!((&a ^ &copy).is_empty());
+}
+
 #[test]
 fn sub() {
     let mut rb1 = (1..4000).collect::<RoaringBitmap>();
@@ -55,6 +200,24 @@ fn sub() {
     assert_eq!(rb3, rb1);
 }
 
+#[test]
+fn sub_owned_shrinks_containers() {
+    // A bitset container, an array container and a container fully removed
+    let rb1 =
+        (0..10_000).chain(100_000..100_010).chain(200_000..200_010).collect::<RoaringBitmap>();
+    let rb2 = (10..10_000).chain(200_000..200_010).collect::<RoaringBitmap>();
+
+    let expected = (0..10).chain(100_000..100_010).collect::<RoaringBitmap>();
+    let difference = rb1.clone() - &rb2;
+    assert_eq!(difference, expected);
+    assert_eq!(difference, &rb1 - &rb2);
+
+    let statistics = difference.statistics();
+    assert_eq!(statistics.n_containers, 2);
+    assert_eq!(statistics.n_array_containers, 2);
+    assert_eq!(statistics.n_bitset_containers, 0);
+}
+
 #[test]
 fn xor() {
     let mut rb1 = (1..4).collect::<RoaringBitmap>();
@@ -76,3 +239,64 @@ fn xor() {
 
     assert_eq!(rb4, rb1);
 }
+
+#[test]
+fn xor_owned_interleaved_containers() {
+    let rb1 = (0..20).map(|i| i << 16).chain(0..3).collect::<RoaringBitmap>();
+    let rb2 = (0..40).map(|i| (i << 16) + 1).chain(1..2).collect::<RoaringBitmap>();
+    let rb3 = (5..50).map(|i| i << 17).chain(0..3).collect::<RoaringBitmap>();
+
+    for (a, b) in [(&rb1, &rb2), (&rb2, &rb1), (&rb1, &rb3), (&rb3, &rb1), (&rb2, &rb3)] {
+        let expected = a ^ b;
+        assert_eq!(expected, a.clone() ^ b.clone());
+        assert_eq!(expected.len(), a.symmetric_difference_len(b));
+    }
+
+    assert!((rb1.clone() ^ rb1).is_empty());
+}
+
+#[test]
+fn empty_operands() {
+    let a = (1..1000).chain(100_000..200_000).collect::<RoaringBitmap>();
+    let empty = RoaringBitmap::new();
+
+    assert_eq!(&a | &empty, a);
+    assert_eq!(&empty | &a, a);
+    assert_eq!(&a & &empty, empty);
+    assert_eq!(&empty & &a, empty);
+    assert_eq!(&a - &empty, a);
+    assert_eq!(&empty - &a, empty);
+    assert_eq!(&a ^ &empty, a);
+    assert_eq!(&empty ^ &a, a);
+
+    assert_eq!(a.clone() | empty.clone(), a);
+    assert_eq!(empty.clone() | a.clone(), a);
+    assert_eq!(a.clone() & empty.clone(), empty);
+    assert_eq!(empty.clone() & a.clone(), empty);
+    assert_eq!(a.clone() - empty.clone(), a);
+    assert_eq!(empty.clone() - a.clone(), empty);
+    assert_eq!(a.clone() ^ empty.clone(), a);
+    assert_eq!(empty.clone() ^ a.clone(), a);
+
+    let mut lhs = a.clone();
+    lhs |= &empty;
+    assert_eq!(lhs, a);
+    lhs ^= &empty;
+    assert_eq!(lhs, a);
+    lhs -= &empty;
+    assert_eq!(lhs, a);
+    lhs &= &empty;
+    assert_eq!(lhs, empty);
+
+    let mut lhs = RoaringBitmap::new();
+    lhs |= &a;
+    assert_eq!(lhs, a);
+    let mut lhs = RoaringBitmap::new();
+    lhs ^= &a;
+    assert_eq!(lhs, a);
+    let mut lhs = RoaringBitmap::new();
+    lhs -= &a;
+    assert_eq!(lhs, empty);
+    lhs &= &a;
+    assert_eq!(lhs, empty);
+}
@@ -16,6 +16,94 @@ fn u32_max() {
     assert!(bitmap.contains_range(4_100_000_000..=u32::MAX));
 }
 
+#[test]
+fn u32_max_boundaries() {
+    let mut bitmap = RoaringBitmap::new();
+    assert_eq!(bitmap.insert_range((u32::MAX - 5)..=u32::MAX), 6);
+    assert_eq!(bitmap.len(), 6);
+    assert_eq!(bitmap.min(), Some(u32::MAX - 5));
+    assert_eq!(bitmap.max(), Some(u32::MAX));
+    assert!(bitmap.iter().eq((u32::MAX - 5)..=u32::MAX));
+    assert_eq!(bitmap.insert_range((u32::MAX - 10)..), 5);
+
+    // Entirely in the last container, which ends up as a bitmap
+    let mut bitmap = RoaringBitmap::new();
+    assert_eq!(bitmap.insert_range(0xFFFF_0000..=0xFFFF_00FF), 256);
+    assert_eq!(bitmap.insert_range(0xFFFF_0000..=u32::MAX), 65536 - 256);
+    assert_eq!(bitmap.len(), 65536);
+    assert!(bitmap.contains_range(0xFFFF_0000..));
+    assert_eq!(bitmap.range_cardinality(0xFFFF_0000..), 65536);
+    assert_eq!(bitmap.remove_range(u32::MAX..=u32::MAX), 1);
+    assert_eq!(bitmap.max(), Some(u32::MAX - 1));
+    assert_eq!(bitmap.remove_range(0xFFFF_0000..), 65535);
+    assert!(bitmap.is_empty());
+
+    // Across the last two containers
+    let mut bitmap = RoaringBitmap::new();
+    assert_eq!(bitmap.insert_range(0xFFFE_FFF0..=u32::MAX), 65536 + 16);
+    assert!(bitmap.iter().eq(0xFFFE_FFF0..=u32::MAX));
+    assert!(bitmap.iter().rev().eq((0xFFFE_FFF0..=u32::MAX).rev()));
+
+    // The full universe
+    let mut bitmap = RoaringBitmap::new();
+    assert_eq!(bitmap.insert_range(0..=u32::MAX), 1 << 32);
+    assert_eq!(bitmap.len(), 1 << 32);
+    assert_eq!(bitmap, RoaringBitmap::full());
+    assert_eq!(bitmap.remove_range(1..u32::MAX), (1 << 32) - 2);
+    assert_eq!(bitmap, RoaringBitmap::from([0, u32::MAX]));
+}
+
+#[test]
+fn all_range_bounds() {
+    let mut bitmap = RoaringBitmap::new();
+    assert_eq!(bitmap.insert_range(..10), 10);
+    assert_eq!(bitmap.insert_range(..=10), 1);
+    assert_eq!(bitmap.insert_range(20..30), 10);
+    assert_eq!(bitmap.insert_range(25..=30), 1);
+    assert_eq!(bitmap.insert_range(u32::MAX - 9..), 10);
+
+    assert!(bitmap.contains_range(..=10));
+    assert!(bitmap.contains_range(..11));
+    assert!(bitmap.contains_range(20..=30));
+    assert!(bitmap.contains_range(u32::MAX - 9..));
+    assert!(!bitmap.contains_range(..));
+
+    assert_eq!(bitmap.range_cardinality(..), 32);
+    assert_eq!(bitmap.range_cardinality(5..), 27);
+    assert_eq!(bitmap.range_cardinality(..25), 16);
+    assert_eq!(bitmap.range_cardinality(..=25), 17);
+
+    assert_eq!(bitmap.remove_range(..5), 5);
+    assert_eq!(bitmap.remove_range(..=5), 1);
+    assert_eq!(bitmap.remove_range(u32::MAX..), 1);
+    assert_eq!(bitmap.remove_range(..), 25);
+    assert!(bitmap.is_empty());
+
+    // The full universe can only be expressed with an unbounded range
+    assert_eq!(bitmap.insert_range(..), u64::from(u32::MAX) + 1);
+    assert!(bitmap.is_full());
+    assert!(bitmap.contains_range(..));
+    assert_eq!(bitmap.range_cardinality(..), u64::from(u32::MAX) + 1);
+}
+
+proptest! {
+    #[test]
+    fn proptest_range_to_u32_max(
+        start in (u32::MAX - 200_000)..=u32::MAX,
+        extra in hash_set((u32::MAX - 200_000)..=u32::MAX, ..=100),
+    ){
+        let mut bitmap = RoaringBitmap::from_iter(extra.iter().cloned());
+        let expected = u64::from(u32::MAX - start) + 1;
+        let already = extra.iter().filter(|&&v| v >= start).count() as u64;
+        prop_assert_eq!(bitmap.insert_range(start..=u32::MAX), expected - already);
+        prop_assert_eq!(bitmap.range_cardinality(start..), expected);
+        prop_assert!(bitmap.contains_range(start..=u32::MAX));
+        prop_assert_eq!(bitmap.max(), Some(u32::MAX));
+        prop_assert_eq!(bitmap.remove_range(start..), expected);
+        prop_assert_eq!(bitmap.len(), extra.len() as u64 - already);
+    }
+}
+
 proptest! {
     #[test]
     fn proptest_range(
@@ -59,7 +59,40 @@ fn rank_bitmap() {
     assert_eq!(bitmap.rank(4999), 5000);
 }
 
+#[test]
+fn insert_and_rank() {
+    let mut bitmap = (0..5000).collect::<RoaringBitmap>();
+
+    assert_eq!(bitmap.insert_and_rank(100), (false, 101));
+    assert_eq!(bitmap.insert_and_rank(100_000), (true, 5001));
+    assert_eq!(bitmap.insert_and_rank(70_000), (true, 5001));
+    assert_eq!(bitmap.insert_and_rank(u32::MAX), (true, 5003));
+    assert_eq!(bitmap.rank(u32::MAX), 5003);
+}
+
+#[test]
+fn insert_and_rank_full_array() {
+    // A full array container becomes a bitset when a new value is inserted
+    let mut bitmap = (0..8192).step_by(2).collect::<RoaringBitmap>();
+
+    assert_eq!(bitmap.insert_and_rank(4094), (false, 2048));
+    assert_eq!(bitmap.insert_and_rank(4095), (true, 2049));
+    assert_eq!(bitmap.insert_and_rank(8191), (true, 4098));
+    assert_eq!(bitmap.validate(), Ok(()));
+}
+
 proptest! {
+    #[test]
+    fn proptest_insert_and_rank(values in vec(..=262_143_u32, ..=1000)) {
+        let mut bitmap = RoaringBitmap::new();
+        for value in values {
+            let expected_new = !bitmap.contains(value);
+            let (inserted, rank) = bitmap.insert_and_rank(value);
+            prop_assert_eq!(inserted, expected_new);
+            prop_assert_eq!(rank, bitmap.rank(value));
+        }
+    }
+
     #[test]
     fn proptest_rank(
         values in btree_set(..=262_143_u32, ..=1000),
@@ -41,12 +41,59 @@ fn select_empty() {
     assert_eq!(bitmap.select(u32::MAX), None);
 }
 
+#[test]
+fn value_at_unchecked() {
+    let mut bitmap = (0..2000).collect::<RoaringBitmap>();
+    bitmap.insert_range(100_000..200_000);
+
+    assert_eq!(bitmap.value_at_unchecked(0), 0);
+    assert_eq!(bitmap.value_at_unchecked(1999), 1999);
+    assert_eq!(bitmap.value_at_unchecked(2000), 100_000);
+    assert_eq!(bitmap.value_at_unchecked(101_999), 199_999);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic]
+fn value_at_unchecked_out_of_bounds() {
+    let bitmap = (0..2000).collect::<RoaringBitmap>();
+    bitmap.value_at_unchecked(2000);
+}
+
+#[test]
+fn bisect_by_cardinality() {
+    let empty = RoaringBitmap::new();
+    assert_eq!(empty.bisect_by_cardinality(), (RoaringBitmap::new(), RoaringBitmap::new()));
+
+    let single = RoaringBitmap::from([0]);
+    assert_eq!(single.bisect_by_cardinality(), (RoaringBitmap::new(), single.clone()));
+
+    let mut bitmap = (0..2000).collect::<RoaringBitmap>();
+    bitmap.insert_range(100_000..200_000);
+    let (lower, upper) = bitmap.bisect_by_cardinality();
+    assert_eq!(lower.len(), 51_000);
+    assert_eq!(upper.len(), 51_000);
+    assert_eq!(upper.min(), Some(149_000));
+    assert_eq!(&lower | &upper, bitmap);
+}
+
 proptest! {
     #[test]
     fn proptest_select(values in btree_set(any::<u32>(), 1000)) {
         let bitmap = RoaringBitmap::from_sorted_iter(values.iter().cloned()).unwrap();
         for (i, value) in values.iter().cloned().enumerate() {
             prop_assert_eq!(bitmap.select(i as u32), Some(value));
+            prop_assert_eq!(bitmap.value_at_unchecked(i as u64), value);
         }
     }
+
+    #[test]
+    fn proptest_bisect_by_cardinality(values in btree_set(any::<u32>(), 0..1000)) {
+        let bitmap = RoaringBitmap::from_sorted_iter(values.iter().cloned()).unwrap();
+        let (lower, upper) = bitmap.bisect_by_cardinality();
+        prop_assert_eq!(lower.len(), bitmap.len() / 2);
+        prop_assert_eq!(upper.len(), bitmap.len() - bitmap.len() / 2);
+        prop_assert!(lower.max() < upper.min() || lower.is_empty());
+        prop_assert_eq!(lower | upper, bitmap);
+    }
 }
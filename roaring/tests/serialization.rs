@@ -2,6 +2,7 @@
 
 extern crate roaring;
 
+use roaring::bitmap::SerializeError;
 use roaring::RoaringBitmap;
 
 // Test data from https://github.com/RoaringBitmap/RoaringFormatSpec/tree/master/testdata
@@ -36,6 +37,53 @@ fn test_deserialize_with_runs_from_provided_data() {
     );
 }
 
+#[test]
+fn test_serialize_into_bounded() {
+    let bitmap = test_data_bitmap();
+    let size = bitmap.serialized_size();
+
+    let mut buffer = vec![];
+    assert_eq!(bitmap.serialize_into_bounded(&mut buffer, size).unwrap(), size);
+    assert_eq!(RoaringBitmap::deserialize_from(&buffer[..]).unwrap(), bitmap);
+
+    let mut buffer = vec![];
+    match bitmap.serialize_into_bounded(&mut buffer, size - 1) {
+        Err(SerializeError::TooLarge { required, max_bytes }) => {
+            assert_eq!((required, max_bytes), (size, size - 1));
+        }
+        result => panic!("unexpected result {result:?}"),
+    }
+    assert!(buffer.is_empty());
+
+    // The budget doesn't protect from a writer failing on its own
+    let mut frame = [0u8; 16];
+    match bitmap.serialize_into_bounded(&mut frame[..], usize::MAX) {
+        Err(SerializeError::Io(error)) => assert_eq!(error.kind(), std::io::ErrorKind::WriteZero),
+        result => panic!("unexpected result {result:?}"),
+    }
+}
+
+#[test]
+fn test_union_with_serialized_provided_data() {
+    let others = (0..10).map(|i| i * 100_000).collect::<RoaringBitmap>();
+    let expected = &test_data_bitmap() | &others;
+
+    for data in [BITMAP_WITHOUT_RUNS, BITMAP_WITH_RUNS] {
+        let mut bitmap = RoaringBitmap::new();
+        bitmap.union_with_serialized(data).unwrap();
+        assert_eq!(bitmap, test_data_bitmap());
+
+        let mut bitmap = others.clone();
+        bitmap.union_with_serialized(data).unwrap();
+        assert_eq!(bitmap, expected);
+
+        let evens = (0..1_000_000).step_by(2).collect::<RoaringBitmap>();
+        let mut bitmap = evens.clone();
+        bitmap.union_with_serialized(data).unwrap();
+        assert_eq!(bitmap, &test_data_bitmap() | &evens);
+    }
+}
+
 #[test]
 fn test_serialize_into_provided_data() {
     let bitmap = test_data_bitmap();
@@ -44,6 +92,35 @@ fn test_serialize_into_provided_data() {
     assert!(BITMAP_WITHOUT_RUNS == &buffer[..]);
 }
 
+#[test]
+fn test_serialized_size_upper_bound() {
+    let bitmap = test_data_bitmap();
+    assert_eq!(bitmap.serialized_size_upper_bound(), bitmap.serialized_size());
+    assert_eq!(RoaringBitmap::new().serialized_size_upper_bound(), 8);
+}
+
+#[test]
+fn test_serialized_size_breakdown() {
+    let bitmap = test_data_bitmap();
+    let breakdown = bitmap.serialized_size_breakdown();
+    let n_containers = bitmap.container_count();
+
+    let mut buffer = vec![];
+    bitmap.serialize_into(&mut buffer).unwrap();
+
+    assert_eq!(breakdown.header_bytes, 8 + 4 * n_containers);
+    assert_eq!(breakdown.offset_bytes, 4 * n_containers);
+    assert_eq!(breakdown.run_bytes, 0);
+    assert_eq!(breakdown.total(), buffer.len());
+
+    // the first offset points right after the header and the offset table
+    let first_offset = &buffer[breakdown.header_bytes..][..4];
+    assert_eq!(
+        u32::from_le_bytes(first_offset.try_into().unwrap()) as usize,
+        breakdown.header_bytes + breakdown.offset_bytes
+    );
+}
+
 #[test]
 fn test_empty() {
     let original = RoaringBitmap::new();
@@ -122,3 +122,14 @@ fn bitmaps() {
 
     assert_eq!(bitmap1, bitmap3);
 }
+
+#[test]
+fn symmetric_difference_with_method() {
+    let mut bitmap1 = (0..2000).collect::<RoaringBitmap>();
+    let bitmap2 = (1000..3000).collect::<RoaringBitmap>();
+    let bitmap3 = (0..1000).chain(2000..3000).collect::<RoaringBitmap>();
+
+    bitmap1.symmetric_difference_with(&bitmap2);
+
+    assert_eq!(bitmap1, bitmap3);
+}
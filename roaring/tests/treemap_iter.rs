@@ -1,6 +1,6 @@
 extern crate roaring;
 mod iter;
-use roaring::RoaringTreemap;
+use roaring::{RoaringBitmap, RoaringTreemap};
 
 use iter::outside_in;
 use proptest::arbitrary::any;
@@ -68,6 +68,36 @@ fn bitmaps_iterator() {
     assert_eq!(clone2, original);
 }
 
+#[test]
+fn from_bitmaps_merges_partitions() {
+    let treemap = RoaringTreemap::from_bitmaps([
+        (2, RoaringBitmap::from_iter(0..100)),
+        (0, RoaringBitmap::new()),
+        (2, RoaringBitmap::from_iter(50..200)),
+        (1, RoaringBitmap::from([7])),
+        (3, RoaringBitmap::new()),
+    ]);
+
+    let expected =
+        (0..200).map(|n| (2 << 32) + n).chain([(1 << 32) + 7]).collect::<RoaringTreemap>();
+    assert_eq!(treemap, expected);
+    assert!(treemap.bitmaps().map(|(p, _)| p).eq([1, 2]));
+    assert!(treemap.bitmaps().all(|(_, b)| !b.is_empty()));
+}
+
+proptest! {
+    #[test]
+    fn bucket_iter(values in btree_set(0..(4u64 << 32), ..=10_000)) {
+        let bitmap = RoaringTreemap::from_sorted_iter(values.iter().cloned()).unwrap();
+
+        // The buckets iterated one after the other give all the values
+        assert!(values.iter().cloned().eq((0..4).flat_map(|high| bitmap.bucket_iter(high))));
+        for (high, inner) in bitmap.bitmaps() {
+            assert_eq!(bitmap.bucket_iter(high).count() as u64, inner.len());
+        }
+    }
+}
+
 proptest! {
     #[test]
     fn iter(values in btree_set(any::<u64>(), ..=10_000)) {
@@ -132,6 +162,50 @@ fn interleaved() {
     assert!(outside_in(values).eq(outside_in(bitmap)));
 }
 
+#[test]
+fn fused() {
+    let treemap = (0..10).chain(5 << 32..(5 << 32) + 10).collect::<RoaringTreemap>();
+
+    let mut iter = treemap.iter();
+    assert_eq!(iter.by_ref().count(), 20);
+    for _ in 0..3 {
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+
+    // The front and back cursors meet in the middle of a bucket
+    let mut iter = treemap.clone().into_iter();
+    assert_eq!(iter.nth(5), Some(5));
+    assert_eq!(iter.nth_back(13), Some(6));
+    for _ in 0..3 {
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    let mut bitmaps = treemap.bitmaps();
+    assert_eq!(bitmaps.by_ref().count(), 2);
+    assert!(bitmaps.next().is_none());
+    assert!(bitmaps.next().is_none());
+}
+
+#[test]
+fn fold_runs() {
+    let mut treemap = RoaringTreemap::from([0, 5, 1 << 32]);
+    treemap.insert_range((1 << 32) - 10..(1 << 32));
+    treemap.insert_range((3 << 32) - 1..=3 << 32);
+    treemap.insert(u64::MAX);
+
+    let runs = treemap.fold_runs(Vec::new(), |mut runs, run| {
+        runs.push(run);
+        runs
+    });
+    assert_eq!(
+        runs,
+        vec![0..=0, 5..=5, (1 << 32) - 10..=1 << 32, (3 << 32) - 1..=3 << 32, u64::MAX..=u64::MAX]
+    );
+    assert_eq!(RoaringTreemap::new().fold_runs(0, |count, _| count + 1), 0);
+}
+
 proptest! {
     #[test]
     fn interleaved_iter(values in btree_set(any::<u64>(), 50_000..=100_000)) {
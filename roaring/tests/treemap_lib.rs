@@ -1,5 +1,5 @@
 extern crate roaring;
-use roaring::RoaringTreemap;
+use roaring::{RoaringBitmap, RoaringTreemap};
 
 #[test]
 fn smoke() {
@@ -49,7 +49,9 @@ fn insert_range() {
     assert!(!bitmap.contains(0x1000));
 
     bitmap.clear();
-    bitmap.insert_range(2 * SIGMA..=4 * SIGMA);
+    assert_eq!(bitmap.insert_range(2 * SIGMA..=4 * SIGMA), 2 * SIGMA + 1);
+    assert_eq!(bitmap.len(), 2 * SIGMA + 1);
+    assert_eq!(bitmap.bitmaps().count(), 3);
 
     assert_eq!(bitmap.min(), Some(2 * SIGMA));
     assert_eq!(bitmap.max(), Some(4 * SIGMA));
@@ -57,6 +59,23 @@ fn insert_range() {
     assert!(bitmap.contains(3 * SIGMA));
 }
 
+#[test]
+fn all_range_bounds() {
+    let mut bitmap = RoaringTreemap::new();
+    assert_eq!(bitmap.insert_range(..10), 10);
+    assert_eq!(bitmap.insert_range(..=10), 1);
+    assert_eq!(bitmap.insert_range(20..30), 10);
+    assert_eq!(bitmap.insert_range(25..=30), 1);
+    assert_eq!(bitmap.insert_range(u64::MAX - 9..), 10);
+    assert_eq!(bitmap.len(), 32);
+
+    assert_eq!(bitmap.remove_range(..5), 5);
+    assert_eq!(bitmap.remove_range(..=5), 1);
+    assert_eq!(bitmap.remove_range(u64::MAX..), 1);
+    assert_eq!(bitmap.remove_range(..), 25);
+    assert!(bitmap.is_empty());
+}
+
 #[test]
 fn remove_range() {
     let ranges = [0u64, 1, 63, 64, 65, 100, 4096 - 1, 4096, 4096 + 1, 65536 - 1];
@@ -69,6 +88,63 @@ fn remove_range() {
     }
 }
 
+#[test]
+fn no_empty_buckets() {
+    let mut treemap = (0..10).chain(5 << 32..(5 << 32) + 10).collect::<RoaringTreemap>();
+
+    for value in 0..10 {
+        treemap.remove(value);
+    }
+    assert_eq!(treemap.bitmaps().count(), 1);
+    treemap.internal_validate().unwrap();
+
+    treemap.remove_range(5 << 32..6 << 32);
+    assert!(treemap.is_empty());
+    assert_eq!(treemap.len(), 0);
+    assert_eq!(treemap.bitmaps().count(), 0);
+    treemap.internal_validate().unwrap();
+
+    let lhs = (0..10).chain(5 << 32..(5 << 32) + 10).collect::<RoaringTreemap>();
+    let rhs = (0..10).collect::<RoaringTreemap>();
+    for result in [&lhs - &rhs, &lhs ^ &rhs, &lhs & &rhs] {
+        assert_eq!(result.bitmaps().count(), 1);
+        result.internal_validate().unwrap();
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn deserialize_skips_empty_buckets() {
+    let mut bytes = vec![];
+    bytes.extend_from_slice(&2u64.to_le_bytes());
+    bytes.extend_from_slice(&1u32.to_le_bytes());
+    RoaringBitmap::new().serialize_into(&mut bytes).unwrap();
+    bytes.extend_from_slice(&5u32.to_le_bytes());
+    RoaringBitmap::from([7]).serialize_into(&mut bytes).unwrap();
+
+    let treemap = RoaringTreemap::deserialize_from(&bytes[..]).unwrap();
+    assert_eq!(treemap.iter().collect::<Vec<u64>>(), vec![(5 << 32) + 7]);
+    assert_eq!(treemap.bitmaps().count(), 1);
+    treemap.internal_validate().unwrap();
+}
+
+#[test]
+fn eq_bitmap() {
+    let bitmap = (0..1000).chain(100_000..200_000).collect::<RoaringBitmap>();
+    let mut treemap = bitmap.iter().map(u64::from).collect::<RoaringTreemap>();
+
+    assert!(treemap.eq_bitmap(&bitmap));
+    assert!(!treemap.eq_bitmap(&RoaringBitmap::new()));
+    assert!(RoaringTreemap::new().eq_bitmap(&RoaringBitmap::new()));
+
+    treemap.remove(500);
+    assert!(!treemap.eq_bitmap(&bitmap));
+
+    let shifted =
+        bitmap.iter().map(|value| (1 << 32) + u64::from(value)).collect::<RoaringTreemap>();
+    assert!(!shifted.eq_bitmap(&bitmap));
+}
+
 #[test]
 fn test_max() {
     let mut bitmap = RoaringTreemap::new();
@@ -43,3 +43,24 @@ fn basic_3() {
             .chain(u32max..(u32max + 2 * (1 << 16))),
     )
 }
+
+#[test]
+fn streaming() {
+    let rb =
+        RoaringTreemap::from_iter((0..1000).chain(1 << 32..(1 << 32) + 100_000).chain([5 << 32]));
+    let mut bytes = vec![];
+    rb.serialize_into(&mut bytes).unwrap();
+
+    // The buckets are written one after the other, the bucket count and the first bucket
+    // are complete when the writer fails in the middle of the second bucket
+    let first_bucket = RoaringTreemap::from_iter(0..1000).serialized_size();
+    let mut partial = vec![0; first_bucket + 100];
+    assert!(rb.serialize_into(&mut partial[..]).is_err());
+    assert_eq!(partial, bytes[..first_bucket + 100]);
+
+    // The buckets are read one after the other, the bytes following the treemap are left
+    bytes.extend_from_slice(b"trailing");
+    let mut reader = &bytes[..];
+    assert_eq!(RoaringTreemap::deserialize_from(&mut reader).unwrap(), rb);
+    assert_eq!(reader, b"trailing");
+}
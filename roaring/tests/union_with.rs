@@ -97,3 +97,14 @@ fn bitmaps() {
 
     assert_eq!(bitmap1, bitmap3);
 }
+
+#[test]
+fn union_with_method() {
+    let mut bitmap1 = (0..2000).collect::<RoaringBitmap>();
+    let bitmap2 = (1000..3000).collect::<RoaringBitmap>();
+    let bitmap3 = (0..3000).collect::<RoaringBitmap>();
+
+    bitmap1.union_with(&bitmap2);
+
+    assert_eq!(bitmap1, bitmap3);
+}